@@ -29,17 +29,20 @@ use crate::cli::Filters;
 pub struct Keep {
     filters: Vec<Box<dyn filter::Filter>>,
     internal_threshold: f64,
+    max_overhang: u64,
 }
 
 impl Keep {
     pub fn new(
         internal_match: f64,
+        max_overhang: u64,
         matches: &std::collections::HashMap<String, clap::ArgMatches>,
     ) -> Self {
         let filters = Vec::new();
         let mut k = Keep {
             filters,
             internal_threshold: internal_match,
+            max_overhang,
         };
 
         if let Some(keep) = matches.get("keep") {
@@ -63,6 +66,10 @@ impl Filters for Keep {
         self.internal_threshold
     }
 
+    fn max_overhang(&self) -> u64 {
+        self.max_overhang
+    }
+
     fn add_filter(&mut self, f: Box<dyn filter::Filter>) {
         self.filters.push(f);
     }
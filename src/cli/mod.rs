@@ -23,6 +23,7 @@ SOFTWARE.
 /* project use */
 use crate::filter;
 use crate::io;
+use crate::type_def;
 
 /* local use */
 pub mod subcommand;
@@ -37,6 +38,9 @@ pub use self::keep::Keep;
 pub mod modifier;
 pub use self::modifier::*;
 
+pub mod stateful;
+pub use self::stateful::Stateful;
+
 /* crates use */
 use clap::{App, Arg, ArgMatches};
 
@@ -64,6 +68,12 @@ pub fn app<'a>() -> App<'a> {
              .default_value("0.8")
              .about("A match is internal match if overhang length > match length * internal threshold this option set internal match")
         )
+        .arg(Arg::new("max-overhang")
+             .takes_value(true)
+             .long("max-overhang")
+             .default_value("1000")
+             .about("A match is internal match if overhang length > max overhang, this option set the maximal overhang length")
+        )
         .arg(Arg::new("compression-out")
              .short('z')
              .takes_value(true)
@@ -75,8 +85,19 @@ pub fn app<'a>() -> App<'a> {
              .short('F')
              .long("format")
              .takes_value(true)
-             .about("Force the format used")
-             .possible_values(&["paf", "m4"])
+             .about("Force the format used, by default format is detected from first record, or from .las extension")
+             .possible_values(&["paf", "gaf", "m4", "mhap", "sam", "bam", "las"])
+        )
+        .arg(Arg::new("las-reads")
+             .long("las-reads")
+             .takes_value(true)
+             .about("Read names and lengths of las input, a DBdump -rhl output or a tsv with read name and length in database order")
+        )
+        .arg(Arg::new("output-format")
+             .long("output-format")
+             .takes_value(true)
+             .about("Format used to write mapping, by default input format is used, sam, bam and las are written in paf")
+             .possible_values(&["paf", "gaf", "m4", "mhap"])
         )
         .subcommand(subcommand::get_keep())
         .subcommand(subcommand::get_drop())
@@ -132,20 +153,32 @@ pub trait Filters {
 
     fn internal_match(&self) -> f64;
 
+    fn max_overhang(&self) -> u64;
+
     fn add_filter(&mut self, f: Box<dyn filter::Filter>);
 
     fn generate(&mut self, m: &clap::ArgMatches) {
         let internal_match = self.internal_match();
+        let max_overhang = self.max_overhang();
         if m.is_present("containment") {
-            self.add_filter(Box::new(filter::Containment::new(internal_match)));
+            self.add_filter(Box::new(filter::Containment::new(
+                internal_match,
+                max_overhang,
+            )));
         }
 
         if m.is_present("internalmatch") {
-            self.add_filter(Box::new(filter::InternalMatch::new(internal_match)));
+            self.add_filter(Box::new(filter::InternalMatch::new(
+                internal_match,
+                max_overhang,
+            )));
         }
 
         if m.is_present("dovetail") {
-            self.add_filter(Box::new(filter::Dovetails::new(internal_match)));
+            self.add_filter(Box::new(filter::Dovetails::new(
+                internal_match,
+                max_overhang,
+            )));
         }
 
         if let Some(length_lower) = m.value_of("length_lower") {
@@ -162,18 +195,146 @@ pub trait Filters {
             )));
         }
 
+        if let Some(identity_lower) = m.value_of("identity_lower") {
+            self.add_filter(Box::new(filter::Identity::new(
+                identity_lower.parse::<f64>().unwrap(),
+                std::cmp::Ordering::Less,
+            )));
+        }
+
+        if let Some(identity_upper) = m.value_of("identity_upper") {
+            self.add_filter(Box::new(filter::Identity::new(
+                identity_upper.parse::<f64>().unwrap(),
+                std::cmp::Ordering::Greater,
+            )));
+        }
+
+        let mapq_missing = m
+            .value_of("mapq_missing")
+            .map(|missing| missing.parse::<u64>().unwrap());
+
+        if let Some(overhang_left_lower) = m.value_of("overhang_left_lower") {
+            self.add_filter(Box::new(filter::Overhang::new(
+                overhang_left_lower.parse::<u64>().unwrap() as f64,
+                std::cmp::Ordering::Less,
+                filter::overhang::OverhangMeasure::Left,
+            )));
+        }
+
+        if let Some(overhang_left_upper) = m.value_of("overhang_left_upper") {
+            self.add_filter(Box::new(filter::Overhang::new(
+                overhang_left_upper.parse::<u64>().unwrap() as f64,
+                std::cmp::Ordering::Greater,
+                filter::overhang::OverhangMeasure::Left,
+            )));
+        }
+
+        if let Some(overhang_right_lower) = m.value_of("overhang_right_lower") {
+            self.add_filter(Box::new(filter::Overhang::new(
+                overhang_right_lower.parse::<u64>().unwrap() as f64,
+                std::cmp::Ordering::Less,
+                filter::overhang::OverhangMeasure::Right,
+            )));
+        }
+
+        if let Some(overhang_right_upper) = m.value_of("overhang_right_upper") {
+            self.add_filter(Box::new(filter::Overhang::new(
+                overhang_right_upper.parse::<u64>().unwrap() as f64,
+                std::cmp::Ordering::Greater,
+                filter::overhang::OverhangMeasure::Right,
+            )));
+        }
+
+        if let Some(overhang_ratio_lower) = m.value_of("overhang_ratio_lower") {
+            self.add_filter(Box::new(filter::Overhang::new(
+                overhang_ratio_lower.parse::<f64>().unwrap(),
+                std::cmp::Ordering::Less,
+                filter::overhang::OverhangMeasure::Ratio,
+            )));
+        }
+
+        if let Some(overhang_ratio_upper) = m.value_of("overhang_ratio_upper") {
+            self.add_filter(Box::new(filter::Overhang::new(
+                overhang_ratio_upper.parse::<f64>().unwrap(),
+                std::cmp::Ordering::Greater,
+                filter::overhang::OverhangMeasure::Ratio,
+            )));
+        }
+
+        if let Some(mapq_lower) = m.value_of("mapq_lower") {
+            self.add_filter(Box::new(filter::MappingQuality::new(
+                mapq_lower.parse::<u64>().unwrap(),
+                std::cmp::Ordering::Less,
+                mapq_missing,
+            )));
+        }
+
+        if let Some(mapq_upper) = m.value_of("mapq_upper") {
+            self.add_filter(Box::new(filter::MappingQuality::new(
+                mapq_upper.parse::<u64>().unwrap(),
+                std::cmp::Ordering::Greater,
+                mapq_missing,
+            )));
+        }
+
         if let Some(name_match) = m.value_of("name_match") {
-            self.add_filter(Box::new(filter::NameMatch::new(name_match)));
+            self.add_filter(Box::new(filter::NameMatch::new(
+                name_match,
+                type_def::WorkOnWichPart::from(m.value_of("name_match_part").unwrap()),
+            )));
+        }
+
+        if let Some(segments) = m.value_of("path_segment") {
+            self.add_filter(Box::new(filter::PathSegment::new(segments.split(','))));
+        }
+
+        if let Some(end_types) = m.values_of("end_type") {
+            self.add_filter(Box::new(filter::EndType::new(
+                end_types,
+                self.internal_match(),
+                self.max_overhang(),
+            )));
+        }
+
+        if let Some(path) = m.value_of("name_list") {
+            self.add_filter(Box::new(filter::NameList::new(
+                path,
+                type_def::WorkOnWichPart::from(m.value_of("name_list_part").unwrap()),
+            )));
+        }
+
+        if let Some(tags) = m.values_of("tag") {
+            for tag in tags {
+                self.add_filter(Box::new(filter::Tag::new(tag)));
+            }
+        }
+
+        if let Some(expression) = m.value_of("expression") {
+            self.add_filter(
+                filter::expression::parse(expression, internal_match, max_overhang, mapq_missing)
+                    .unwrap_or_else(|e| {
+                        eprintln!("Error in expression {:?}: {}", expression, e);
+                        std::process::exit(1);
+                    }),
+            );
+        }
+
+        if let Some(strand) = m.value_of("strand") {
+            self.add_filter(Box::new(filter::Strand::new(strand == "same")));
         }
 
         if m.is_present("same_name") {
             self.add_filter(Box::new(filter::SameName::new()));
         }
 
+        let sequence_length_part =
+            type_def::WorkOnWichPart::from(m.value_of("sequence_length_part").unwrap());
+
         if let Some(sequence_length_lower) = m.value_of("sequence_length_lower") {
             self.add_filter(Box::new(filter::SequenceLength::new(
                 sequence_length_lower.parse::<u64>().unwrap(),
                 std::cmp::Ordering::Less,
+                sequence_length_part.clone(),
             )));
         }
 
@@ -181,6 +342,7 @@ pub trait Filters {
             self.add_filter(Box::new(filter::SequenceLength::new(
                 sequence_length_lower.parse::<u64>().unwrap(),
                 std::cmp::Ordering::Greater,
+                sequence_length_part,
             )));
         }
     }
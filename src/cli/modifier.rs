@@ -31,6 +31,7 @@ pub struct Modifier {
 impl Modifier {
     pub fn new(
         internal_match: f64,
+        max_overhang: u64,
         matches: &std::collections::HashMap<String, clap::ArgMatches>,
     ) -> Self {
         let mut modifiers: Vec<Box<dyn generator::Modifier>> = Vec::new();
@@ -52,9 +53,47 @@ impl Modifier {
         if let Some(m) = matches.get("gfa") {
             modifiers.push(Box::new(generator::Gfa1::new(
                 m.value_of("output").unwrap().to_string(),
-                m.is_present("internalmatch"),
-                m.is_present("containment"),
-                internal_match,
+                generator::GfaOutput {
+                    gfa2: m.value_of("gfa_version").unwrap() == "2",
+                    reads: m.value_of("reads").map(|p| p.to_string()),
+                    transitive_reduction: if m.is_present("transitive_reduction") {
+                        Some(m.value_of("fuzz").unwrap().parse::<u64>().unwrap())
+                    } else {
+                        None
+                    },
+                    trim_tips: if m.is_present("trim_tips") {
+                        Some((
+                            m.value_of("tip_max_reads")
+                                .unwrap()
+                                .parse::<usize>()
+                                .unwrap(),
+                            m.value_of("tip_max_length")
+                                .unwrap()
+                                .parse::<u64>()
+                                .unwrap(),
+                        ))
+                    } else {
+                        None
+                    },
+                    pop_bubbles: if m.is_present("pop_bubbles") {
+                        Some(
+                            m.value_of("bubble_max_length")
+                                .unwrap()
+                                .parse::<u64>()
+                                .unwrap(),
+                        )
+                    } else {
+                        None
+                    },
+                    unitig: m.is_present("unitig"),
+                    unitig_fasta: m.value_of("unitig_fasta").map(|p| p.to_string()),
+                },
+                io::gfa::Gfa1::new(
+                    m.is_present("internalmatch"),
+                    m.is_present("containment"),
+                    internal_match,
+                    max_overhang,
+                ),
             )))
         }
 
@@ -0,0 +1,125 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* project use */
+use crate::filter;
+use crate::io;
+
+/// Filter that need to see all mapping, input is read twice when one of this filter isn't streaming, first to add mapping, second to write mapping passing filters.
+pub struct Stateful {
+    filters: Vec<Box<dyn filter::StatefulFilter>>,
+}
+
+impl Stateful {
+    pub fn new(
+        internal_match: f64,
+        max_overhang: u64,
+        matches: &std::collections::HashMap<String, clap::ArgMatches>,
+    ) -> Self {
+        let mut filters: Vec<Box<dyn filter::StatefulFilter>> = Vec::new();
+
+        if let Some(m) = matches.get("keep") {
+            if let Some(n) = m.value_of("top_n") {
+                filters.push(Box::new(filter::TopN::new(
+                    n.parse::<usize>().unwrap(),
+                    filter::rank::Criterion::from(m.value_of("top_n_by").unwrap()),
+                    m.is_present("top_n_per_end"),
+                    internal_match,
+                    max_overhang,
+                )));
+            }
+        }
+
+        if let Some(m) = matches.get("drop") {
+            let criterion = filter::rank::Criterion::from(m.value_of("duplicate_by").unwrap());
+
+            if m.is_present("coverage_max")
+                || m.is_present("coverage_median_factor")
+                || m.is_present("coverage_table")
+            {
+                filters.push(Box::new(filter::Coverage::new(
+                    filter::coverage::CoverageCount::from(m.value_of("coverage_by").unwrap()),
+                    m.value_of("coverage_max")
+                        .map(|max| max.parse::<f64>().unwrap()),
+                    m.value_of("coverage_median_factor")
+                        .map(|factor| factor.parse::<f64>().unwrap()),
+                    m.value_of("coverage_table"),
+                )));
+            }
+
+            match m.value_of("duplicate") {
+                Some("exact") => filters.push(Box::new(filter::Dedup::new(criterion))),
+                Some("approximate") => filters.push(Box::new(filter::ApproximateDedup::new(
+                    criterion,
+                    m.value_of("duplicate_table_size")
+                        .unwrap()
+                        .parse::<usize>()
+                        .unwrap(),
+                ))),
+                _ => (),
+            }
+        }
+
+        Stateful { filters }
+    }
+
+    /// No filter need to read input twice
+    pub fn streaming(&self) -> bool {
+        self.filters.iter().all(|f| f.streaming())
+    }
+
+    /// Give mapping to filters, when input is read twice streaming filters only see mapping during second pass.
+    pub fn add(&mut self, index: usize, r: &dyn io::MappingRecord) {
+        let two_pass = !self.streaming();
+
+        for f in self.filters.iter_mut() {
+            if !two_pass || !f.streaming() {
+                f.add(index, r);
+            }
+        }
+    }
+
+    pub fn finish(&mut self) {
+        for f in self.filters.iter_mut() {
+            f.finish();
+        }
+    }
+
+    /// Must be called after `add` of the same mapping, only when input is read once.
+    pub fn pass(&self, index: usize, r: &dyn io::MappingRecord) -> bool {
+        self.filters.iter().all(|f| f.pass(index, r))
+    }
+
+    /// Give mapping to streaming filters and return true if mapping pass all filters, used during second read of input.
+    pub fn second_pass(&mut self, index: usize, r: &dyn io::MappingRecord) -> bool {
+        let mut keep = true;
+        for f in self.filters.iter_mut() {
+            if f.streaming() {
+                f.add(index, r);
+            }
+
+            keep &= f.pass(index, r);
+        }
+
+        keep
+    }
+}
@@ -59,6 +59,72 @@ pub fn get_keep<'a>() -> App<'a> {
                 .takes_value(true)
                 .about("Keep only mapping with length upper than value"),
         )
+        .arg(
+            Arg::new("identity_lower")
+                .long("identity-lower")
+                .takes_value(true)
+                .about("Keep only mapping with identity lower than value, identity is computed from de:f or dv:f tag when present"),
+        )
+        .arg(
+            Arg::new("identity_upper")
+                .long("identity-upper")
+                .takes_value(true)
+                .about("Keep only mapping with identity upper than value, identity is computed from de:f or dv:f tag when present"),
+        )
+        .arg(
+            Arg::new("overhang_left_lower")
+                .long("overhang-left-lower")
+                .takes_value(true)
+                .about("Keep only mapping with left overhang lower than value, overhang is the shortest unaligned part of reads on this side of alignment"),
+        )
+        .arg(
+            Arg::new("overhang_left_upper")
+                .long("overhang-left-upper")
+                .takes_value(true)
+                .about("Keep only mapping with left overhang upper than value, overhang is the shortest unaligned part of reads on this side of alignment"),
+        )
+        .arg(
+            Arg::new("overhang_right_lower")
+                .long("overhang-right-lower")
+                .takes_value(true)
+                .about("Keep only mapping with right overhang lower than value, overhang is the shortest unaligned part of reads on this side of alignment"),
+        )
+        .arg(
+            Arg::new("overhang_right_upper")
+                .long("overhang-right-upper")
+                .takes_value(true)
+                .about("Keep only mapping with right overhang upper than value, overhang is the shortest unaligned part of reads on this side of alignment"),
+        )
+        .arg(
+            Arg::new("overhang_ratio_lower")
+                .long("overhang-ratio-lower")
+                .takes_value(true)
+                .about("Keep only mapping with overhang ratio lower than value, overhang ratio is the sum of overhangs divide by alignment length"),
+        )
+        .arg(
+            Arg::new("overhang_ratio_upper")
+                .long("overhang-ratio-upper")
+                .takes_value(true)
+                .about("Keep only mapping with overhang ratio upper than value, overhang ratio is the sum of overhangs divide by alignment length"),
+        )
+        .arg(
+            Arg::new("mapq_lower")
+                .long("mapq-lower")
+                .takes_value(true)
+                .about("Keep only mapping with mapping quality lower than value"),
+        )
+        .arg(
+            Arg::new("mapq_upper")
+                .long("mapq-upper")
+                .takes_value(true)
+                .about("Keep only mapping with mapping quality upper than value"),
+        )
+        .arg(
+            Arg::new("mapq_missing")
+                .long("mapq-missing")
+                .takes_value(true)
+                .about("Mapping quality used when it's unavailable (255 or format without mapping quality), by default mapping quality filters and mapq comparisons of expression never match these mapping"),
+        )
         .arg(
             Arg::new("name_match")
                 .short('n')
@@ -66,12 +132,100 @@ pub fn get_keep<'a>() -> App<'a> {
                 .takes_value(true)
                 .about("Keep only mapping where one reads match with regex"),
         )
+        .arg(
+            Arg::new("name_match_part")
+                .long("name-match-part")
+                .takes_value(true)
+                .default_value("either")
+                .possible_values(&["query", "target", "either", "both"])
+                .about("Name match is apply on query, target, either (query or target) or both (query and target)"),
+        )
+        .arg(
+            Arg::new("path_segment")
+                .short('p')
+                .long("path-segment")
+                .takes_value(true)
+                .about("Keep only mapping where target path use one of segments, segments are separated by comma"),
+        )
+        .arg(
+            Arg::new("end_type")
+                .long("end-type")
+                .takes_value(true)
+                .use_delimiter(true)
+                .possible_values(&[
+                    "suffix-prefix",
+                    "prefix-suffix",
+                    "suffix-suffix",
+                    "prefix-prefix",
+                    "query-suffix",
+                    "query-prefix",
+                    "target-suffix",
+                    "target-prefix",
+                ])
+                .about("Keep only mapping where reads ends involved in overlap match one of types, types are separated by comma, suffix is read end (3'), prefix is read begin (5'), containments and internal matches never match"),
+        )
+        .arg(
+            Arg::new("name_list")
+                .short('N')
+                .long("name-list")
+                .takes_value(true)
+                .about("Keep only mapping where read name is in file, file could be a list of name, a fasta, a fastq or a yacrd report"),
+        )
+        .arg(
+            Arg::new("name_list_part")
+                .long("name-list-part")
+                .takes_value(true)
+                .default_value("either")
+                .possible_values(&["query", "target", "either", "both"])
+                .about("Name list is apply on query, target, either (query or target) or both (query and target)"),
+        )
         .arg(
             Arg::new("same_name")
                 .short('m')
                 .long("same-name")
                 .about("Keep only mapping where reads have same name"),
         )
+        .arg(
+            Arg::new("strand")
+                .long("strand")
+                .takes_value(true)
+                .possible_values(&["same", "opposite"])
+                .about("Keep only mapping where reads are on same or opposite strand"),
+        )
+        .arg(
+            Arg::new("top_n")
+                .long("top-n")
+                .takes_value(true)
+                .about("Keep only the N best mapping of each read, a mapping is keep if it's among the best of one of its reads, input is read twice and only score of best mapping are stored in memory"),
+        )
+        .arg(
+            Arg::new("top_n_by")
+                .long("top-n-by")
+                .takes_value(true)
+                .default_value("length")
+                .possible_values(&["length", "identity", "matches"])
+                .about("Criterion used to rank mapping of a read, on equal value first mapping is the best"),
+        )
+        .arg(
+            Arg::new("top_n_per_end")
+                .long("top-n-per-end")
+                .about("Keep the N best mapping of each read end instead of each read, containments and internal matches are dropped"),
+        )
+        .arg(
+            Arg::new("expression")
+                .short('e')
+                .long("expr")
+                .takes_value(true)
+                .about("Keep only mapping where boolean expression is true, expression combine with and, or, not and parentheses the classifications containment, dovetail, internal, self and comparisons (<, <=, >, >=, =, !=) of fields length, length_a, length_b, identity, matches, block_length, mapq with a number"),
+        )
+        .arg(
+            Arg::new("tag")
+                .short('t')
+                .long("tag")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .about("Keep only mapping where optional field match test NAME[OPERATOR VALUE], without operator tag must exist, operators are =, !=, ~ (regex), <, <=, > and >="),
+        )
         .arg(
             Arg::new("sequence_length_lower")
                 .short('s')
@@ -86,6 +240,14 @@ pub fn get_keep<'a>() -> App<'a> {
                 .takes_value(true)
                 .about("Keep only mapping where one reads have length upper than value"),
         )
+        .arg(
+            Arg::new("sequence_length_part")
+                .long("sequence-length-part")
+                .takes_value(true)
+                .default_value("either")
+                .possible_values(&["query", "target", "either", "both"])
+                .about("Sequence length is apply on query, target, either (query or target) or both (query and target)"),
+        )
 }
 
 pub fn get_drop<'a>() -> clap::App<'a> {
@@ -124,6 +286,72 @@ pub fn get_drop<'a>() -> clap::App<'a> {
                 .takes_value(true)
                 .about("Drop mapping with length upper than value"),
         )
+        .arg(
+            Arg::new("identity_lower")
+                .long("identity-lower")
+                .takes_value(true)
+                .about("Drop mapping with identity lower than value, identity is computed from de:f or dv:f tag when present"),
+        )
+        .arg(
+            Arg::new("identity_upper")
+                .long("identity-upper")
+                .takes_value(true)
+                .about("Drop mapping with identity upper than value, identity is computed from de:f or dv:f tag when present"),
+        )
+        .arg(
+            Arg::new("overhang_left_lower")
+                .long("overhang-left-lower")
+                .takes_value(true)
+                .about("Drop mapping with left overhang lower than value, overhang is the shortest unaligned part of reads on this side of alignment"),
+        )
+        .arg(
+            Arg::new("overhang_left_upper")
+                .long("overhang-left-upper")
+                .takes_value(true)
+                .about("Drop mapping with left overhang upper than value, overhang is the shortest unaligned part of reads on this side of alignment"),
+        )
+        .arg(
+            Arg::new("overhang_right_lower")
+                .long("overhang-right-lower")
+                .takes_value(true)
+                .about("Drop mapping with right overhang lower than value, overhang is the shortest unaligned part of reads on this side of alignment"),
+        )
+        .arg(
+            Arg::new("overhang_right_upper")
+                .long("overhang-right-upper")
+                .takes_value(true)
+                .about("Drop mapping with right overhang upper than value, overhang is the shortest unaligned part of reads on this side of alignment"),
+        )
+        .arg(
+            Arg::new("overhang_ratio_lower")
+                .long("overhang-ratio-lower")
+                .takes_value(true)
+                .about("Drop mapping with overhang ratio lower than value, overhang ratio is the sum of overhangs divide by alignment length"),
+        )
+        .arg(
+            Arg::new("overhang_ratio_upper")
+                .long("overhang-ratio-upper")
+                .takes_value(true)
+                .about("Drop mapping with overhang ratio upper than value, overhang ratio is the sum of overhangs divide by alignment length"),
+        )
+        .arg(
+            Arg::new("mapq_lower")
+                .long("mapq-lower")
+                .takes_value(true)
+                .about("Drop mapping with mapping quality lower than value"),
+        )
+        .arg(
+            Arg::new("mapq_upper")
+                .long("mapq-upper")
+                .takes_value(true)
+                .about("Drop mapping with mapping quality upper than value"),
+        )
+        .arg(
+            Arg::new("mapq_missing")
+                .long("mapq-missing")
+                .takes_value(true)
+                .about("Mapping quality used when it's unavailable (255 or format without mapping quality), by default mapping quality filters and mapq comparisons of expression never match these mapping"),
+        )
         .arg(
             Arg::new("name_match")
                 .short('n')
@@ -131,12 +359,129 @@ pub fn get_drop<'a>() -> clap::App<'a> {
                 .takes_value(true)
                 .about("Drop mapping where one reads match with regex"),
         )
+        .arg(
+            Arg::new("name_match_part")
+                .long("name-match-part")
+                .takes_value(true)
+                .default_value("either")
+                .possible_values(&["query", "target", "either", "both"])
+                .about("Name match is apply on query, target, either (query or target) or both (query and target)"),
+        )
+        .arg(
+            Arg::new("path_segment")
+                .short('p')
+                .long("path-segment")
+                .takes_value(true)
+                .about("Drop mapping where target path use one of segments, segments are separated by comma"),
+        )
+        .arg(
+            Arg::new("end_type")
+                .long("end-type")
+                .takes_value(true)
+                .use_delimiter(true)
+                .possible_values(&[
+                    "suffix-prefix",
+                    "prefix-suffix",
+                    "suffix-suffix",
+                    "prefix-prefix",
+                    "query-suffix",
+                    "query-prefix",
+                    "target-suffix",
+                    "target-prefix",
+                ])
+                .about("Drop mapping where reads ends involved in overlap match one of types, types are separated by comma, suffix is read end (3'), prefix is read begin (5'), containments and internal matches never match"),
+        )
+        .arg(
+            Arg::new("name_list")
+                .short('N')
+                .long("name-list")
+                .takes_value(true)
+                .about("Drop mapping where read name is in file, file could be a list of name, a fasta, a fastq or a yacrd report"),
+        )
+        .arg(
+            Arg::new("name_list_part")
+                .long("name-list-part")
+                .takes_value(true)
+                .default_value("either")
+                .possible_values(&["query", "target", "either", "both"])
+                .about("Name list is apply on query, target, either (query or target) or both (query and target)"),
+        )
         .arg(
             Arg::new("same_name")
                 .short('m')
                 .long("same-name")
                 .about("Drop mapping where reads have same name"),
         )
+        .arg(
+            Arg::new("strand")
+                .long("strand")
+                .takes_value(true)
+                .possible_values(&["same", "opposite"])
+                .about("Drop mapping where reads are on same or opposite strand"),
+        )
+        .arg(
+            Arg::new("duplicate")
+                .long("duplicate")
+                .takes_value(true)
+                .possible_values(&["exact", "approximate"])
+                .about("Drop mapping of a pair of reads (A→B and B→A are the same pair) except the best one, exact mode store all pairs in memory and read input twice, approximate mode use a fixed size table and can keep some duplicate"),
+        )
+        .arg(
+            Arg::new("duplicate_by")
+                .long("duplicate-by")
+                .takes_value(true)
+                .default_value("length")
+                .possible_values(&["length", "identity", "matches"])
+                .about("Criterion used to choose best mapping of a pair, on equal value first mapping is the best"),
+        )
+        .arg(
+            Arg::new("duplicate_table_size")
+                .long("duplicate-table-size")
+                .takes_value(true)
+                .default_value("4194304")
+                .about("Number of pairs stored by approximate duplicate removal, each pair use 16 bytes"),
+        )
+        .arg(
+            Arg::new("coverage_max")
+                .long("coverage-max")
+                .takes_value(true)
+                .about("Drop mapping involving reads with a count upper than value, input is read twice and only counts are stored in memory"),
+        )
+        .arg(
+            Arg::new("coverage_median_factor")
+                .long("coverage-median-factor")
+                .takes_value(true)
+                .about("Drop mapping involving reads with a count upper than value times the median count of reads, input is read twice and only counts are stored in memory"),
+        )
+        .arg(
+            Arg::new("coverage_by")
+                .long("coverage-by")
+                .takes_value(true)
+                .default_value("overlaps")
+                .possible_values(&["overlaps", "bases"])
+                .about("Count number of mapping or summed aligned bases of read in all mapping (bases in many mapping are counted many times)"),
+        )
+        .arg(
+            Arg::new("coverage_table")
+                .long("coverage-table")
+                .takes_value(true)
+                .about("Write count of each read in this csv file, can be used without coverage limit"),
+        )
+        .arg(
+            Arg::new("expression")
+                .short('e')
+                .long("expr")
+                .takes_value(true)
+                .about("Drop mapping where boolean expression is true, expression combine with and, or, not and parentheses the classifications containment, dovetail, internal, self and comparisons (<, <=, >, >=, =, !=) of fields length, length_a, length_b, identity, matches, block_length, mapq with a number"),
+        )
+        .arg(
+            Arg::new("tag")
+                .short('t')
+                .long("tag")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .about("Drop mapping where optional field match test NAME[OPERATOR VALUE], without operator tag must exist, operators are =, !=, ~ (regex), <, <=, > and >="),
+        )
         .arg(
             Arg::new("sequence_length_lower")
                 .short('s')
@@ -151,6 +496,14 @@ pub fn get_drop<'a>() -> clap::App<'a> {
                 .takes_value(true)
                 .about("Drop mapping where one reads have length upper than value"),
         )
+        .arg(
+            Arg::new("sequence_length_part")
+                .long("sequence-length-part")
+                .takes_value(true)
+                .default_value("either")
+                .possible_values(&["query", "target", "either", "both"])
+                .about("Sequence length is apply on query, target, either (query or target) or both (query and target)"),
+        )
 }
 
 pub fn get_rename<'a>() -> clap::App<'a> {
@@ -201,7 +554,7 @@ pub fn get_index<'a>() -> clap::App<'a> {
 pub fn get_gfa<'a>() -> clap::App<'a> {
     App::new("gfa")
         .setting(clap::AppSettings::AllowExternalSubcommands)
-        .about("fpa generate a overlap graph in gfa1 format with mapping passing filter")
+        .about("fpa generate a overlap graph in gfa1 or gfa2 format with mapping passing filter")
         .arg(
             Arg::new("output")
                 .short('o')
@@ -209,7 +562,7 @@ pub fn get_gfa<'a>() -> clap::App<'a> {
                 .required(true)
                 .takes_value(true)
                 .about(
-                    "Write mapping passing filter in gfa1 graph format in path passed as parameter",
+                    "Write mapping passing filter in gfa graph format in path passed as parameter",
                 ),
         )
         .arg(
@@ -224,4 +577,77 @@ pub fn get_gfa<'a>() -> clap::App<'a> {
                 .long("internalmatch")
                 .about("Keep internal match overlap"),
         )
+        .arg(
+            Arg::new("gfa_version")
+                .short('v')
+                .long("gfa-version")
+                .takes_value(true)
+                .default_value("1")
+                .possible_values(&["1", "2"])
+                .about("Gfa version, in gfa2 overlaps are written as edges with coordinates on both reads"),
+        )
+        .arg(
+            Arg::new("reads")
+                .short('r')
+                .long("reads")
+                .takes_value(true)
+                .about("Fasta or fastq file (can be compressed) used to write sequence of segments, reads file is streamed after mapping is read"),
+        )
+        .arg(
+            Arg::new("transitive_reduction")
+                .long("transitive-reduction")
+                .about("Remove transitive edges of graph, contained reads are removed before, number of removed edges is reported on stderr"),
+        )
+        .arg(
+            Arg::new("fuzz")
+                .long("fuzz")
+                .takes_value(true)
+                .default_value("1000")
+                .about("Length tolerance of transitive reduction, a path must be shorter than direct edge plus fuzz to remove it"),
+        )
+        .arg(
+            Arg::new("trim_tips")
+                .long("trim-tips")
+                .about("Remove tips, dead-end paths joining graph on a branching read, after transitive reduction, contained reads are removed before, number of removed reads is reported on stderr"),
+        )
+        .arg(
+            Arg::new("tip_max_reads")
+                .long("tip-max-reads")
+                .takes_value(true)
+                .default_value("4")
+                .about("Maximal number of reads in a removed tip"),
+        )
+        .arg(
+            Arg::new("tip_max_length")
+                .long("tip-max-length")
+                .takes_value(true)
+                .default_value("50000")
+                .about("Maximal length in bases of a removed tip"),
+        )
+        .arg(
+            Arg::new("pop_bubbles")
+                .long("pop-bubbles")
+                .about("Pop simple bubbles, the path with most reads is kept, after tip trimming, contained reads are removed before, number of popped bubbles is reported on stderr"),
+        )
+        .arg(
+            Arg::new("bubble_max_length")
+                .long("bubble-max-length")
+                .takes_value(true)
+                .default_value("50000")
+                .about("Maximal length in bases of a popped bubble"),
+        )
+        .arg(
+            Arg::new("unitig")
+                .short('u')
+                .long("unitig")
+                .conflicts_with("gfa_version")
+                .about("Write a gfa1 graph of unitigs, non-branching paths of reads, position of reads in unitig are written in `a` lines"),
+        )
+        .arg(
+            Arg::new("unitig_fasta")
+                .long("unitig-fasta")
+                .takes_value(true)
+                .requires_all(&["unitig", "reads"])
+                .about("Write unitigs sequence in fasta, sequence is a concatenation of reads sequence without consensus"),
+        )
 }
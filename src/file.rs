@@ -32,6 +32,53 @@ pub fn get_input(input_name: &str) -> (Box<dyn io::Read>, niffler::compression::
     }
 }
 
+/// Temporary file removed when dropped, also on error or panic.
+pub struct TemporaryFile {
+    path: std::path::PathBuf,
+}
+
+impl TemporaryFile {
+    /// Create a new empty file in temporary directory, an existing file (or symlink) is never reused.
+    fn new() -> (Self, std::fs::File) {
+        for i in 0.. {
+            let path = std::env::temp_dir().join(format!("fpa_{}_{}.tmp", std::process::id(), i));
+
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(file) => return (TemporaryFile { path }, file),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => panic!("Can't create temporary file: {}", e),
+            }
+        }
+
+        unreachable!()
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+}
+
+impl Drop for TemporaryFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Copy input in a temporary file, used when input is read twice and can't be reopen.
+pub fn copy_to_temporary(mut input: Box<dyn io::Read>) -> TemporaryFile {
+    let (temporary, file) = TemporaryFile::new();
+
+    let mut copy = BufWriter::new(file);
+    io::copy(&mut input, &mut copy).expect("Error durring copy of input in temporary file");
+    io::Write::flush(&mut copy).expect("Error durring copy of input in temporary file");
+
+    temporary
+}
+
 pub fn choose_compression(
     input_compression: niffler::compression::Format,
     compression_set: bool,
@@ -60,3 +107,21 @@ pub fn get_output(output_name: &str, format: niffler::compression::Format) -> Bo
         _ => niffler::to_path(output_name, format, niffler::compression::Level::One).unwrap(),
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn temporary() {
+        let first = copy_to_temporary(Box::new(&b"content"[..]));
+        let second = copy_to_temporary(Box::new(&b""[..]));
+        assert_ne!(first.path(), second.path());
+        assert_eq!(std::fs::read(first.path()).unwrap(), b"content");
+
+        let path = first.path().to_path_buf();
+        drop(first);
+        assert!(!path.exists());
+    }
+}
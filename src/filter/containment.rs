@@ -28,17 +28,21 @@ use crate::io;
 
 pub struct Containment {
     internal_threshold: f64,
+    max_overhang: u64,
 }
 
 impl Containment {
-    pub fn new(internal_threshold: f64) -> Self {
-        Containment { internal_threshold }
+    pub fn new(internal_threshold: f64, max_overhang: u64) -> Self {
+        Containment {
+            internal_threshold,
+            max_overhang,
+        }
     }
 }
 
 impl filter::Filter for Containment {
     fn run(&self, r: &dyn io::MappingRecord) -> bool {
-        if filter::InternalMatch::new(self.internal_threshold).run(r) {
+        if filter::InternalMatch::new(self.internal_threshold, self.max_overhang).run(r) {
             return false;
         }
 
@@ -86,14 +90,14 @@ mod test {
 
     #[test]
     fn positif() {
-        let nm = Containment::new(0.8);
+        let nm = Containment::new(0.8, 1000);
 
         assert_eq!(nm.run(&*RECORD), true);
     }
 
     #[test]
     fn negatif() {
-        let nm = Containment::new(0.8);
+        let nm = Containment::new(0.8, 1000);
 
         assert_ne!(nm.run(&*RECORD), false);
     }
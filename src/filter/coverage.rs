@@ -0,0 +1,251 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* project use */
+use crate::filter;
+use crate::io;
+
+/* standard use */
+use std::collections::HashMap;
+
+/// Value counted for each read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoverageCount {
+    /// number of mapping
+    Overlaps,
+    /// summed aligned bases of read in all mapping, bases in many mapping are counted many times
+    Bases,
+}
+
+impl From<&str> for CoverageCount {
+    fn from(count: &str) -> Self {
+        match count {
+            "bases" => CoverageCount::Bases,
+            _ => CoverageCount::Overlaps,
+        }
+    }
+}
+
+/// Drop mapping involving reads with too many overlaps, count is upper than `max` or than `median_factor` times the median count.
+pub struct Coverage {
+    count: CoverageCount,
+    max: Option<f64>,
+    median_factor: Option<f64>,
+    table_path: Option<String>,
+    counts: HashMap<String, u64>,
+    limit: f64,
+}
+
+impl Coverage {
+    pub fn new(
+        count: CoverageCount,
+        max: Option<f64>,
+        median_factor: Option<f64>,
+        table_path: Option<&str>,
+    ) -> Self {
+        Coverage {
+            count,
+            max,
+            median_factor,
+            table_path: table_path.map(|p| p.to_string()),
+            counts: HashMap::new(),
+            limit: f64::INFINITY,
+        }
+    }
+
+    fn increment(&mut self, read: String, value: u64) {
+        *self.counts.entry(read).or_insert(0) += value;
+    }
+
+    fn median(&self) -> f64 {
+        let mut values: Vec<u64> = self.counts.values().copied().collect();
+        values.sort_unstable();
+
+        match values.len() {
+            0 => 0.0,
+            n if n % 2 == 0 => (values[n / 2 - 1] + values[n / 2]) as f64 / 2.0,
+            n => values[n / 2] as f64,
+        }
+    }
+
+    fn high(&self, read: &str) -> bool {
+        self.counts
+            .get(read)
+            .map_or(false, |count| *count as f64 > self.limit)
+    }
+
+    /// Write count of each read in csv format, reads are sorted by name
+    pub fn write_table<W: std::io::Write>(&self, writer: W) {
+        let mut writer = csv::Writer::from_writer(writer);
+
+        let mut counts: Vec<(&String, &u64)> = self.counts.iter().collect();
+        counts.sort_unstable();
+
+        for (read, count) in counts {
+            writer
+                .write_record([read, &count.to_string()])
+                .expect("Error durring write count table");
+        }
+    }
+}
+
+impl filter::StatefulFilter for Coverage {
+    fn add(&mut self, _index: usize, r: &dyn io::MappingRecord) {
+        let (value_a, value_b) = match self.count {
+            CoverageCount::Overlaps => (1, 1),
+            CoverageCount::Bases => (r.end_a() - r.begin_a(), r.end_b() - r.begin_b()),
+        };
+
+        self.increment(r.read_a(), value_a);
+        if r.read_a() != r.read_b() {
+            self.increment(r.read_b(), value_b);
+        }
+    }
+
+    fn finish(&mut self) {
+        let median_limit = self.median_factor.map(|factor| factor * self.median());
+
+        self.limit = match (self.max, median_limit) {
+            (Some(max), Some(median)) => max.min(median),
+            (Some(max), None) => max,
+            (None, Some(median)) => median,
+            (None, None) => f64::INFINITY,
+        };
+
+        if let Some(path) = &self.table_path {
+            self.write_table(
+                std::fs::File::create(path).expect("Can't create file to write count table"),
+            );
+        }
+    }
+
+    fn pass(&self, _index: usize, r: &dyn io::MappingRecord) -> bool {
+        !self.high(&r.read_a()) && !self.high(&r.read_b())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use filter::StatefulFilter;
+
+    fn record(read_a: &str, read_b: &str, length: u64) -> io::paf::Record {
+        io::paf::Record {
+            read_a: read_a.to_string(),
+            length_a: 20000,
+            begin_a: 0,
+            end_a: length,
+            strand: '+',
+            read_b: read_b.to_string(),
+            length_b: 20000,
+            begin_b: 20000 - length,
+            end_b: 20000,
+            nb_match_base: length,
+            nb_base: length,
+            mapping_quality: 255,
+            sam_field: Vec::new(),
+            position: (0, 50),
+        }
+    }
+
+    lazy_static! {
+        static ref RECORDS: Vec<io::paf::Record> = vec![
+            record("repeat", "read_1", 1000),
+            record("repeat", "read_2", 1000),
+            record("repeat", "read_3", 1000),
+            record("read_4", "repeat", 1000),
+            record("read_1", "read_2", 5000),
+            record("read_3", "read_4", 5000),
+        ];
+    }
+
+    fn count_and_select(mut coverage: Coverage) -> (Coverage, Vec<bool>) {
+        for (i, r) in RECORDS.iter().enumerate() {
+            coverage.add(i, r);
+        }
+        coverage.finish();
+
+        let selected = RECORDS
+            .iter()
+            .enumerate()
+            .map(|(i, r)| coverage.pass(i, r))
+            .collect();
+
+        (coverage, selected)
+    }
+
+    #[test]
+    fn count() {
+        let (coverage, _) =
+            count_and_select(Coverage::new(CoverageCount::Overlaps, None, None, None));
+        assert_eq!(coverage.counts["repeat"], 4);
+        assert_eq!(coverage.counts["read_1"], 2);
+        assert_eq!(coverage.median(), 2.0);
+
+        let (coverage, _) = count_and_select(Coverage::new(CoverageCount::Bases, None, None, None));
+        assert_eq!(coverage.counts["repeat"], 4000);
+        assert_eq!(coverage.counts["read_1"], 6000);
+    }
+
+    #[test]
+    fn max() {
+        let (_, selected) = count_and_select(Coverage::new(
+            CoverageCount::Overlaps,
+            Some(3.0),
+            None,
+            None,
+        ));
+
+        assert_eq!(selected, vec![false, false, false, false, true, true]);
+    }
+
+    #[test]
+    fn median_factor() {
+        let (_, selected) = count_and_select(Coverage::new(
+            CoverageCount::Overlaps,
+            None,
+            Some(1.5),
+            None,
+        ));
+        assert_eq!(selected, vec![false, false, false, false, true, true]);
+
+        let (_, selected) =
+            count_and_select(Coverage::new(CoverageCount::Bases, None, Some(1.5), None));
+        assert_eq!(selected, vec![true, true, true, true, true, true]);
+    }
+
+    #[test]
+    fn table() {
+        let mut coverage = Coverage::new(CoverageCount::Overlaps, None, None, None);
+        coverage.add(0, &RECORDS[0]);
+        coverage.add(1, &RECORDS[1]);
+
+        let mut table = Vec::new();
+        coverage.write_table(&mut table);
+
+        assert_eq!(
+            std::str::from_utf8(&table).unwrap(),
+            "read_1,1\nread_2,1\nrepeat,2\n"
+        );
+    }
+}
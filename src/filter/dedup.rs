@@ -0,0 +1,279 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* project use */
+use crate::filter;
+use crate::io;
+use filter::rank::{Candidate, Criterion};
+
+/* standard use */
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// Canonical form of a mapping: reads ordered by name, relative strand and coordinates of each read in the same order.
+///
+/// Coordinates are on forward strand of each read and strand is relative, so a B→A mapping is transformed in a A→B mapping by swapping reads and their coordinates.
+pub type Key = ((String, String), char, [u64; 4]);
+
+pub fn canonical(r: &dyn io::MappingRecord) -> Key {
+    let (a, b) = (r.read_a(), r.read_b());
+
+    if a <= b {
+        (
+            (a, b),
+            r.strand(),
+            [r.begin_a(), r.end_a(), r.begin_b(), r.end_b()],
+        )
+    } else {
+        (
+            (b, a),
+            r.strand(),
+            [r.begin_b(), r.end_b(), r.begin_a(), r.end_a()],
+        )
+    }
+}
+
+/// Reads of a mapping ordered by name, A→B and B→A mapping have the same pair
+pub type Pair = (String, String);
+
+/// Keep only the best mapping of each pair of reads. All pairs are stored in memory.
+pub struct Dedup {
+    criterion: Criterion,
+    best: HashMap<Pair, Candidate>,
+    selected: HashSet<usize>,
+}
+
+impl Dedup {
+    pub fn new(criterion: Criterion) -> Self {
+        Dedup {
+            criterion,
+            best: HashMap::new(),
+            selected: HashSet::new(),
+        }
+    }
+}
+
+impl filter::StatefulFilter for Dedup {
+    fn add(&mut self, index: usize, r: &dyn io::MappingRecord) {
+        let candidate = Candidate {
+            score: self.criterion.score(r),
+            index,
+        };
+
+        let (pair, _, _) = canonical(r);
+        match self.best.get_mut(&pair) {
+            Some(best) => {
+                if candidate > *best {
+                    *best = candidate;
+                }
+            }
+            None => {
+                self.best.insert(pair, candidate);
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        self.selected = self.best.drain().map(|(_, c)| c.index).collect();
+    }
+
+    fn pass(&self, index: usize, _r: &dyn io::MappingRecord) -> bool {
+        self.selected.contains(&index)
+    }
+}
+
+/// Keep a mapping only if it's better than previous mapping of the same pair of reads.
+///
+/// Pairs are stored in a table with a fixed number of slots, when two pairs share a slot the older one is forgotten. The best mapping of each pair is always keep but some duplicate can remain.
+pub struct ApproximateDedup {
+    criterion: Criterion,
+    // pair hash (0 is empty) and best score
+    table: Vec<(u64, f64)>,
+    last: Option<(usize, bool)>,
+}
+
+impl ApproximateDedup {
+    pub fn new(criterion: Criterion, table_size: usize) -> Self {
+        ApproximateDedup {
+            criterion,
+            table: vec![(0, 0.0); table_size.max(1)],
+            last: None,
+        }
+    }
+
+    fn hash(pair: &Pair) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        pair.hash(&mut hasher);
+
+        hasher.finish().max(1)
+    }
+}
+
+impl filter::StatefulFilter for ApproximateDedup {
+    fn add(&mut self, index: usize, r: &dyn io::MappingRecord) {
+        let hash = Self::hash(&canonical(r).0);
+        let score = self.criterion.score(r);
+
+        let position = (hash % self.table.len() as u64) as usize;
+        let slot = &mut self.table[position];
+        let keep = slot.0 != hash || score > slot.1;
+        if keep {
+            *slot = (hash, score);
+        }
+
+        self.last = Some((index, keep));
+    }
+
+    fn streaming(&self) -> bool {
+        true
+    }
+
+    fn pass(&self, index: usize, _r: &dyn io::MappingRecord) -> bool {
+        self.last == Some((index, true))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use filter::StatefulFilter;
+
+    fn record(
+        read_a: &str,
+        (begin_a, end_a): (u64, u64),
+        strand: char,
+        read_b: &str,
+        (begin_b, end_b): (u64, u64),
+        nb_match_base: u64,
+    ) -> io::paf::Record {
+        io::paf::Record {
+            read_a: read_a.to_string(),
+            length_a: 20000,
+            begin_a,
+            end_a,
+            strand,
+            read_b: read_b.to_string(),
+            length_b: 20000,
+            begin_b,
+            end_b,
+            nb_match_base,
+            nb_base: 5000,
+            mapping_quality: 255,
+            sam_field: Vec::new(),
+            position: (0, 50),
+        }
+    }
+
+    lazy_static! {
+        static ref RECORDS: Vec<io::paf::Record> = vec![
+            record("read_1", (15000, 20000), '+', "read_2", (0, 5000), 4000),
+            // mirror of first mapping
+            record("read_2", (0, 5000), '+', "read_1", (15000, 20000), 4000),
+            record("read_1", (15000, 20000), '-', "read_3", (15000, 20000), 4000),
+            // mirror of third mapping with small coordinates differences
+            record("read_3", (15010, 20000), '-', "read_1", (15000, 19990), 4500),
+            record("read_2", (15000, 20000), '+', "read_3", (0, 5000), 4000),
+            // same pair as first mapping but other locus, a repeat
+            record("read_1", (0, 5000), '+', "read_2", (10000, 15000), 4000),
+            // same pair and locus as first mapping but other strand
+            record("read_1", (15000, 20000), '-', "read_2", (0, 5000), 4000),
+        ];
+    }
+
+    #[test]
+    fn canonical_pair() {
+        assert_eq!(
+            canonical(&RECORDS[0]),
+            (
+                ("read_1".to_string(), "read_2".to_string()),
+                '+',
+                [15000, 20000, 0, 5000]
+            )
+        );
+        assert_eq!(canonical(&RECORDS[0]), canonical(&RECORDS[1]));
+        assert_eq!(
+            canonical(&RECORDS[3]),
+            (
+                ("read_1".to_string(), "read_3".to_string()),
+                '-',
+                [15000, 19990, 15010, 20000]
+            )
+        );
+        assert_ne!(canonical(&RECORDS[0]), canonical(&RECORDS[4]));
+        assert_ne!(canonical(&RECORDS[0]), canonical(&RECORDS[5]));
+        assert_ne!(canonical(&RECORDS[0]), canonical(&RECORDS[6]));
+    }
+
+    #[test]
+    fn exact() {
+        let mut dedup = Dedup::new(Criterion::Matches);
+        for (i, r) in RECORDS.iter().enumerate() {
+            dedup.add(i, r);
+        }
+        dedup.finish();
+
+        let selected: Vec<bool> = RECORDS
+            .iter()
+            .enumerate()
+            .map(|(i, r)| dedup.pass(i, r))
+            .collect();
+
+        // mirror, repeat and other strand mapping of read_1 read_2 are removed
+        assert_eq!(selected, vec![true, false, false, true, true, false, false]);
+    }
+
+    #[test]
+    fn approximate() {
+        let mut dedup = ApproximateDedup::new(Criterion::Matches, 1024);
+
+        let selected: Vec<bool> = RECORDS
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                dedup.add(i, r);
+                dedup.pass(i, r)
+            })
+            .collect();
+
+        // read_1 read_3 pair is write two time, second mapping is better
+        assert_eq!(selected, vec![true, false, true, true, true, false, false]);
+    }
+
+    #[test]
+    fn approximate_collision() {
+        let mut dedup = ApproximateDedup::new(Criterion::Matches, 1);
+
+        let selected: Vec<bool> = RECORDS
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                dedup.add(i, r);
+                dedup.pass(i, r)
+            })
+            .collect();
+
+        // with one slot, only consecutive duplicate are removed
+        assert_eq!(selected, vec![true, false, true, true, true, true, false]);
+    }
+}
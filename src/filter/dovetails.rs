@@ -28,18 +28,22 @@ use crate::io;
 
 pub struct Dovetails {
     internal_threshold: f64,
+    max_overhang: u64,
 }
 
 impl Dovetails {
-    pub fn new(internal_threshold: f64) -> Self {
-        Dovetails { internal_threshold }
+    pub fn new(internal_threshold: f64, max_overhang: u64) -> Self {
+        Dovetails {
+            internal_threshold,
+            max_overhang,
+        }
     }
 }
 
 impl filter::Filter for Dovetails {
     fn run(&self, r: &dyn io::MappingRecord) -> bool {
-        !filter::InternalMatch::new(self.internal_threshold).run(r)
-            && !filter::Containment::new(self.internal_threshold).run(r)
+        !filter::InternalMatch::new(self.internal_threshold, self.max_overhang).run(r)
+            && !filter::Containment::new(self.internal_threshold, self.max_overhang).run(r)
     }
 }
 
@@ -72,14 +76,14 @@ mod test {
 
     #[test]
     fn positif() {
-        let nm = Dovetails::new(0.8);
+        let nm = Dovetails::new(0.8, 1000);
 
         assert_eq!(nm.run(&*RECORD), true);
     }
 
     #[test]
     fn negatif() {
-        let nm = Dovetails::new(0.8);
+        let nm = Dovetails::new(0.8, 1000);
 
         assert_ne!(nm.run(&*RECORD), false);
     }
@@ -0,0 +1,137 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* project use */
+use crate::filter;
+use crate::io;
+use io::gfa::OverlapEnd;
+
+pub struct EndType {
+    ends: Vec<OverlapEnd>,
+    internal_threshold: f64,
+    max_overhang: u64,
+}
+
+impl EndType {
+    /// Build filter from end type name, `suffix-prefix`, `prefix-suffix`, `suffix-suffix`, `prefix-prefix` (query end first) or `query-suffix`, `query-prefix`, `target-suffix`, `target-prefix` (any overlap use this end), containments and internal matches never pass
+    pub fn new<'a>(
+        names: impl Iterator<Item = &'a str>,
+        internal_threshold: f64,
+        max_overhang: u64,
+    ) -> Self {
+        let mut ends = Vec::new();
+
+        for name in names {
+            ends.extend_from_slice(match name {
+                "suffix-prefix" => &[OverlapEnd::SuffixPrefix][..],
+                "prefix-suffix" => &[OverlapEnd::PrefixSuffix],
+                "suffix-suffix" => &[OverlapEnd::SuffixSuffix],
+                "prefix-prefix" => &[OverlapEnd::PrefixPrefix],
+                "query-suffix" => &[OverlapEnd::SuffixPrefix, OverlapEnd::SuffixSuffix],
+                "query-prefix" => &[OverlapEnd::PrefixSuffix, OverlapEnd::PrefixPrefix],
+                "target-suffix" => &[OverlapEnd::PrefixSuffix, OverlapEnd::SuffixSuffix],
+                "target-prefix" => &[OverlapEnd::SuffixPrefix, OverlapEnd::PrefixPrefix],
+                _ => panic!("Unknown end type {}", name),
+            });
+        }
+
+        EndType {
+            ends,
+            internal_threshold,
+            max_overhang,
+        }
+    }
+}
+
+impl filter::Filter for EndType {
+    fn run(&self, r: &dyn io::MappingRecord) -> bool {
+        OverlapEnd::classify(r, self.internal_threshold, self.max_overhang)
+            .map_or(false, |end| self.ends.contains(&end))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use filter::Filter;
+
+    lazy_static! {
+        static ref RECORD: io::paf::Record = {
+            io::paf::Record {
+                read_a: "read_1".to_string(),
+                length_a: 20000,
+                begin_a: 15000,
+                end_a: 20000,
+                strand: '+',
+                read_b: "read_2".to_string(),
+                length_b: 20000,
+                begin_b: 0,
+                end_b: 5000,
+                nb_match_base: 5000,
+                nb_base: 5000,
+                mapping_quality: 255,
+                sam_field: Vec::new(),
+                position: (0, 50),
+            }
+        };
+    }
+
+    #[test]
+    fn positif() {
+        assert!(EndType::new(["suffix-prefix"].iter().copied(), 0.8, 1000).run(&*RECORD));
+        assert!(EndType::new(["query-suffix"].iter().copied(), 0.8, 1000).run(&*RECORD));
+        assert!(EndType::new(
+            ["prefix-prefix", "target-prefix"].iter().copied(),
+            0.8,
+            1000
+        )
+        .run(&*RECORD));
+    }
+
+    #[test]
+    fn negatif() {
+        let containment = io::paf::Record {
+            begin_a: 10000,
+            end_a: 15000,
+            length_b: 5000,
+            ..RECORD.clone()
+        };
+        let internal = io::paf::Record {
+            begin_a: 10000,
+            end_a: 15000,
+            begin_b: 2000,
+            end_b: 7000,
+            ..RECORD.clone()
+        };
+        let all_ends = ["query-suffix", "query-prefix"];
+
+        assert!(!EndType::new(all_ends.iter().copied(), 0.8, 1000).run(&containment));
+        assert!(!EndType::new(all_ends.iter().copied(), 0.8, 1000).run(&internal));
+
+        assert!(!EndType::new(["prefix-suffix"].iter().copied(), 0.8, 1000).run(&*RECORD));
+        assert!(
+            !EndType::new(["query-prefix", "target-suffix"].iter().copied(), 0.8, 1000)
+                .run(&*RECORD)
+        );
+    }
+}
@@ -0,0 +1,483 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* project use */
+use crate::filter;
+use crate::io;
+
+/* standard use */
+use std::cmp::Ordering;
+
+pub struct And {
+    filters: Vec<Box<dyn filter::Filter>>,
+}
+
+impl filter::Filter for And {
+    fn run(&self, r: &dyn io::MappingRecord) -> bool {
+        self.filters.iter().all(|x| x.run(r))
+    }
+}
+
+pub struct Or {
+    filters: Vec<Box<dyn filter::Filter>>,
+}
+
+impl filter::Filter for Or {
+    fn run(&self, r: &dyn io::MappingRecord) -> bool {
+        self.filters.iter().any(|x| x.run(r))
+    }
+}
+
+pub struct Not {
+    filter: Box<dyn filter::Filter>,
+}
+
+impl filter::Filter for Not {
+    fn run(&self, r: &dyn io::MappingRecord) -> bool {
+        !self.filter.run(r)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Length,
+    LengthA,
+    LengthB,
+    Identity,
+    Matches,
+    BlockLength,
+    /// value used when mapping quality is unavailable
+    MappingQuality(Option<u64>),
+}
+
+impl Field {
+    fn from_name(name: &str, mapq_missing: Option<u64>) -> Option<Self> {
+        match name {
+            "length" => Some(Field::Length),
+            "length_a" => Some(Field::LengthA),
+            "length_b" => Some(Field::LengthB),
+            "identity" => Some(Field::Identity),
+            "matches" => Some(Field::Matches),
+            "block_length" => Some(Field::BlockLength),
+            "mapq" => Some(Field::MappingQuality(mapq_missing)),
+            _ => None,
+        }
+    }
+
+    fn value(&self, r: &dyn io::MappingRecord) -> Option<f64> {
+        match self {
+            Field::Length => Some(r.length() as f64),
+            Field::LengthA => Some(r.length_a() as f64),
+            Field::LengthB => Some(r.length_b() as f64),
+            Field::Identity => Some(filter::Identity::identity(r)),
+            Field::Matches => Some(r.matches() as f64),
+            Field::BlockLength => Some(r.block_length() as f64),
+            Field::MappingQuality(missing) => {
+                filter::MappingQuality::quality(r, *missing).map(|quality| quality as f64)
+            }
+        }
+    }
+}
+
+/// Compare a record field to a value, a missing value never match.
+pub struct Compare {
+    field: Field,
+    value: f64,
+    accepted: Vec<Ordering>,
+}
+
+impl filter::Filter for Compare {
+    fn run(&self, r: &dyn io::MappingRecord) -> bool {
+        self.field
+            .value(r)
+            .and_then(|v| v.partial_cmp(&self.value))
+            .map_or(false, |o| self.accepted.contains(&o))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Word(String),
+    Operator(String),
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' => (),
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '<' | '>' | '=' | '!' => {
+                let mut operator = c.to_string();
+                if chars.peek() == Some(&'=') {
+                    operator.push(chars.next().unwrap());
+                }
+                tokens.push(Token::Operator(operator));
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' || c == '.' => {
+                let mut word = c.to_string();
+                while let Some(n) = chars.peek() {
+                    if n.is_ascii_alphanumeric() || *n == '_' || *n == '.' {
+                        word.push(chars.next().unwrap());
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Word(word));
+            }
+            _ => return Err(format!("unexpected character '{}'", c)),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parse a number with an optional k, m or g suffix (b can follow suffix).
+fn parse_number(word: &str) -> Result<f64, String> {
+    let lower = word.to_lowercase();
+    let number = lower.strip_suffix('b').unwrap_or(&lower);
+    let (number, factor) = match number.chars().last() {
+        Some('k') => (&number[..number.len() - 1], 1e3),
+        Some('m') => (&number[..number.len() - 1], 1e6),
+        Some('g') => (&number[..number.len() - 1], 1e9),
+        _ => (number, 1.0),
+    };
+
+    number
+        .parse::<f64>()
+        .map(|n| n * factor)
+        .map_err(|_| format!("'{}' isn't a number", word))
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    internal_threshold: f64,
+    max_overhang: u64,
+    mapq_missing: Option<u64>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        self.position += 1;
+        self.tokens.get(self.position - 1)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w == keyword)
+    }
+
+    fn or(&mut self) -> Result<Box<dyn filter::Filter>, String> {
+        let mut filters = vec![self.and()?];
+        while self.is_keyword("or") {
+            self.position += 1;
+            filters.push(self.and()?);
+        }
+
+        if filters.len() == 1 {
+            Ok(filters.pop().unwrap())
+        } else {
+            Ok(Box::new(Or { filters }))
+        }
+    }
+
+    fn and(&mut self) -> Result<Box<dyn filter::Filter>, String> {
+        let mut filters = vec![self.not()?];
+        while self.is_keyword("and") {
+            self.position += 1;
+            filters.push(self.not()?);
+        }
+
+        if filters.len() == 1 {
+            Ok(filters.pop().unwrap())
+        } else {
+            Ok(Box::new(And { filters }))
+        }
+    }
+
+    fn not(&mut self) -> Result<Box<dyn filter::Filter>, String> {
+        if self.is_keyword("not") {
+            self.position += 1;
+            Ok(Box::new(Not {
+                filter: self.not()?,
+            }))
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Box<dyn filter::Filter>, String> {
+        let internal_threshold = self.internal_threshold;
+        let max_overhang = self.max_overhang;
+
+        match self.next().cloned() {
+            Some(Token::Open) => {
+                let filter = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(filter),
+                    _ => Err("missing ')'".to_string()),
+                }
+            }
+            Some(Token::Word(word)) => match word.as_str() {
+                "containment" => Ok(Box::new(filter::Containment::new(
+                    internal_threshold,
+                    max_overhang,
+                ))),
+                "dovetail" => Ok(Box::new(filter::Dovetails::new(
+                    internal_threshold,
+                    max_overhang,
+                ))),
+                "internal" => Ok(Box::new(filter::InternalMatch::new(
+                    internal_threshold,
+                    max_overhang,
+                ))),
+                "self" => Ok(Box::new(filter::SameName::new())),
+                name => {
+                    let field = Field::from_name(name, self.mapq_missing)
+                        .ok_or_else(|| format!("unknown field or classification '{}'", name))?;
+                    self.comparison(field)
+                }
+            },
+            Some(token) => Err(format!("unexpected token {:?}", token)),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+
+    fn comparison(&mut self, field: Field) -> Result<Box<dyn filter::Filter>, String> {
+        let accepted = match self.next() {
+            Some(Token::Operator(op)) => match op.as_str() {
+                "<" => vec![Ordering::Less],
+                "<=" => vec![Ordering::Less, Ordering::Equal],
+                ">" => vec![Ordering::Greater],
+                ">=" => vec![Ordering::Greater, Ordering::Equal],
+                "=" | "==" => vec![Ordering::Equal],
+                "!=" => vec![Ordering::Less, Ordering::Greater],
+                _ => return Err(format!("unknown operator '{}'", op)),
+            },
+            _ => return Err(format!("missing comparison operator after {:?}", field)),
+        };
+
+        let value = match self.next() {
+            Some(Token::Word(word)) => parse_number(word)?,
+            _ => return Err(format!("missing value after {:?} comparison", field)),
+        };
+
+        Ok(Box::new(Compare {
+            field,
+            value,
+            accepted,
+        }))
+    }
+}
+
+/// Compile a boolean expression in a filter.
+///
+/// Expression combine with `and`, `or`, `not` and parentheses the classifications `containment`, `dovetail`, `internal`, `self` and comparisons (`<`, `<=`, `>`, `>=`, `=`, `!=`) of fields `length`, `length_a`, `length_b`, `identity`, `matches`, `block_length` and `mapq` with a number. Unavailable mapping quality is replaced by mapq_missing, if it's None `mapq` comparisons never match.
+pub fn parse(
+    expression: &str,
+    internal_threshold: f64,
+    max_overhang: u64,
+    mapq_missing: Option<u64>,
+) -> Result<Box<dyn filter::Filter>, String> {
+    let tokens = tokenize(expression)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        position: 0,
+        internal_threshold,
+        max_overhang,
+        mapq_missing,
+    };
+
+    let filter = parser.or()?;
+    match parser.peek() {
+        None => Ok(filter),
+        Some(token) => Err(format!("unexpected token {:?}", token)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    lazy_static! {
+        static ref CONTAINMENT: io::paf::Record = {
+            io::paf::Record {
+                read_a: "read_1".to_string(),
+                length_a: 5000,
+                begin_a: 0,
+                end_a: 5000,
+                strand: '+',
+                read_b: "read_2".to_string(),
+                length_b: 20000,
+                begin_b: 5000,
+                end_b: 10000,
+                nb_match_base: 4900,
+                nb_base: 5000,
+                mapping_quality: 255,
+                sam_field: Vec::new(),
+                position: (0, 50),
+            }
+        };
+        static ref DOVETAIL: io::paf::Record = {
+            io::paf::Record {
+                read_a: "read_1".to_string(),
+                length_a: 20000,
+                begin_a: 15000,
+                end_a: 20000,
+                strand: '+',
+                read_b: "read_2".to_string(),
+                length_b: 20000,
+                begin_b: 0,
+                end_b: 5000,
+                nb_match_base: 4000,
+                nb_base: 5000,
+                mapping_quality: 30,
+                sam_field: Vec::new(),
+                position: (0, 50),
+            }
+        };
+    }
+
+    fn run(expression: &str, record: &dyn io::MappingRecord) -> bool {
+        parse(expression, 0.8, 1000, None).unwrap().run(record)
+    }
+
+    #[test]
+    fn tokens() {
+        assert_eq!(
+            tokenize("not(length>=10kb)").unwrap(),
+            vec![
+                Token::Word("not".to_string()),
+                Token::Open,
+                Token::Word("length".to_string()),
+                Token::Operator(">=".to_string()),
+                Token::Word("10kb".to_string()),
+                Token::Close,
+            ]
+        );
+        assert!(tokenize("length > 'a'").is_err());
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(parse_number("0.95").unwrap(), 0.95);
+        assert_eq!(parse_number("10kb").unwrap(), 10_000.0);
+        assert_eq!(parse_number("2M").unwrap(), 2_000_000.0);
+        assert!(parse_number("ten").is_err());
+    }
+
+    #[test]
+    fn classification() {
+        assert!(run("containment", &*CONTAINMENT));
+        assert!(!run("dovetail", &*CONTAINMENT));
+        assert!(run("dovetail", &*DOVETAIL));
+        assert!(!run("internal or self", &*DOVETAIL));
+    }
+
+    #[test]
+    fn comparison() {
+        assert!(run("length = 5000", &*CONTAINMENT));
+        assert!(run("length >= 5k and length <= 5k", &*CONTAINMENT));
+        assert!(!run("length != 5000", &*CONTAINMENT));
+        assert!(run("identity > 0.95", &*CONTAINMENT));
+        assert!(run("length_b > 10000 and length_a < 10000", &*CONTAINMENT));
+        assert!(run("matches = 4000 and block_length = 5000", &*DOVETAIL));
+        assert!(run("mapq >= 30", &*DOVETAIL));
+        assert!(!run("mapq >= 30", &*CONTAINMENT));
+        assert!(!run("mapq < 30", &*CONTAINMENT));
+    }
+
+    #[test]
+    fn mapq_missing() {
+        let expression = parse("mapq >= 20", 0.8, 1000, Some(20)).unwrap();
+        let quality = filter::MappingQuality::new(20, Ordering::Less, Some(20));
+
+        assert!(expression.run(&*CONTAINMENT));
+        assert_eq!(
+            expression.run(&*CONTAINMENT),
+            !filter::Filter::run(&quality, &*CONTAINMENT)
+        );
+        assert!(!parse("mapq >= 20", 0.8, 1000, Some(10))
+            .unwrap()
+            .run(&*CONTAINMENT));
+    }
+
+    #[test]
+    fn identity_tag() {
+        let record = io::paf::Record {
+            sam_field: vec!["dv:f:0.2".to_string()],
+            ..CONTAINMENT.clone()
+        };
+        let identity = filter::Identity::new(0.95, Ordering::Greater);
+
+        assert!(!run("identity > 0.95", &record));
+        assert_eq!(
+            run("identity > 0.95", &record),
+            filter::Filter::run(&identity, &record)
+        );
+    }
+
+    #[test]
+    fn precedence() {
+        // and bind tighter than or
+        assert!(run("dovetail or containment and identity > 2", &*DOVETAIL));
+        assert!(run(
+            "containment and identity < 0.0 or dovetail",
+            &*DOVETAIL
+        ));
+        assert!(!run(
+            "(dovetail or containment) and identity > 0.9",
+            &*DOVETAIL
+        ));
+        assert!(run("not dovetail and containment", &*CONTAINMENT));
+        assert!(!run("not (dovetail or containment)", &*CONTAINMENT));
+        assert!(!run(
+            "containment and not (identity > 0.95 or length > 10kb)",
+            &*DOVETAIL
+        ));
+        assert!(!run(
+            "containment and not (identity > 0.95 or length > 10kb)",
+            &*CONTAINMENT
+        ));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(parse("", 0.8, 1000, None).is_err());
+        assert!(parse("(containment", 0.8, 1000, None).is_err());
+        assert!(parse("containment dovetail", 0.8, 1000, None).is_err());
+        assert!(parse("length >", 0.8, 1000, None).is_err());
+        assert!(parse("length 10", 0.8, 1000, None).is_err());
+        assert!(parse("unknown > 10", 0.8, 1000, None).is_err());
+        assert!(parse("not", 0.8, 1000, None).is_err());
+    }
+}
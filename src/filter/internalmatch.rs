@@ -29,25 +29,50 @@ use std::cmp::{max, min};
 
 pub struct InternalMatch {
     internal_threshold: f64,
+    max_overhang: u64,
 }
 
 impl InternalMatch {
-    pub fn new(internal_threshold: f64) -> Self {
-        InternalMatch { internal_threshold }
+    pub fn new(internal_threshold: f64, max_overhang: u64) -> Self {
+        InternalMatch {
+            internal_threshold,
+            max_overhang,
+        }
     }
 }
 
+/// Overhangs on left and right of read A, each overhang is the shortest unaligned part of reads on this side of alignment.
+pub fn overhangs(r: &dyn io::MappingRecord) -> (u64, u64) {
+    if r.strand() == '+' {
+        (
+            min(r.begin_a(), r.begin_b()),
+            min(r.length_a() - r.end_a(), r.length_b() - r.end_b()),
+        )
+    } else {
+        (
+            min(r.begin_a(), r.length_b() - r.end_b()),
+            min(r.length_a() - r.end_a(), r.begin_b()),
+        )
+    }
+}
+
+/// Longest alignment span of the two reads.
+pub fn mapping_length(r: &dyn io::MappingRecord) -> u64 {
+    max(r.end_a() - r.begin_a(), r.end_b() - r.begin_b())
+}
+
 impl filter::Filter for InternalMatch {
     fn run(&self, r: &dyn io::MappingRecord) -> bool {
-        let overhang = if r.strand() == '+' {
-            min(r.begin_a(), r.begin_b()) + min(r.length_a() - r.end_a(), r.length_b() - r.end_b())
-        } else {
-            min(r.begin_a(), r.length_b() - r.end_b()) + min(r.begin_b(), r.length_a() - r.end_a())
-        };
+        let (left, right) = overhangs(r);
+        let overhang = left + right;
 
-        let maplen = max(r.end_a() - r.begin_a(), r.end_b() - r.begin_b());
+        let maplen = mapping_length(r);
 
-        overhang > min(1000, (maplen as f64 * self.internal_threshold) as u64)
+        overhang
+            > min(
+                self.max_overhang,
+                (maplen as f64 * self.internal_threshold) as u64,
+            )
     }
 }
 
@@ -80,15 +105,29 @@ mod test {
 
     #[test]
     fn positif() {
-        let nm = InternalMatch::new(0.8);
+        let nm = InternalMatch::new(0.8, 1000);
 
         assert_eq!(nm.run(&*RECORD), true);
     }
 
     #[test]
     fn negatif() {
-        let nm = InternalMatch::new(0.8);
+        let nm = InternalMatch::new(0.8, 1000);
 
         assert_ne!(nm.run(&*RECORD), false);
     }
+
+    #[test]
+    fn max_overhang() {
+        let record = io::paf::Record {
+            begin_a: 1000,
+            end_a: 19000,
+            begin_b: 1000,
+            end_b: 19000,
+            ..RECORD.clone()
+        };
+
+        assert!(InternalMatch::new(0.8, 1000).run(&record));
+        assert!(!InternalMatch::new(0.8, 5000).run(&record));
+    }
 }
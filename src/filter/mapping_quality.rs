@@ -0,0 +1,121 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* project use */
+use crate::filter;
+use crate::io;
+
+/// Mapping quality value meaning mapping quality is unavailable.
+const UNAVAILABLE: u64 = 255;
+
+pub struct MappingQuality {
+    quality_threshold: u64,
+    ordering: std::cmp::Ordering,
+    missing: Option<u64>,
+}
+
+impl MappingQuality {
+    /// If mapping quality is unavailable (255 or format without mapping quality) missing value is used, if missing is None filter never match.
+    pub fn new(quality_threshold: u64, ord: std::cmp::Ordering, missing: Option<u64>) -> Self {
+        MappingQuality {
+            quality_threshold,
+            ordering: ord,
+            missing,
+        }
+    }
+
+    /// Mapping quality of record, missing value is used if mapping quality is unavailable.
+    pub fn quality(r: &dyn io::MappingRecord, missing: Option<u64>) -> Option<u64> {
+        r.mapping_quality()
+            .filter(|quality| *quality != UNAVAILABLE)
+            .or(missing)
+    }
+}
+
+impl filter::Filter for MappingQuality {
+    fn run(&self, r: &dyn io::MappingRecord) -> bool {
+        match Self::quality(r, self.missing) {
+            Some(quality) => quality.cmp(&self.quality_threshold) == self.ordering,
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use filter::Filter;
+
+    lazy_static! {
+        static ref RECORD: io::paf::Record = {
+            io::paf::Record {
+                read_a: "read_1".to_string(),
+                length_a: 5000,
+                begin_a: 0,
+                end_a: 5000,
+                strand: '+',
+                read_b: "read_2".to_string(),
+                length_b: 20000,
+                begin_b: 5000,
+                end_b: 10000,
+                nb_match_base: 500,
+                nb_base: 500,
+                mapping_quality: 20,
+                sam_field: Vec::new(),
+                position: (0, 50),
+            }
+        };
+        static ref RECORD_UNAVAILABLE: io::paf::Record = {
+            io::paf::Record {
+                mapping_quality: 255,
+                ..RECORD.clone()
+            }
+        };
+    }
+
+    #[test]
+    fn positif() {
+        let mut nm = MappingQuality::new(30, std::cmp::Ordering::Less, None);
+
+        assert!(nm.run(&*RECORD));
+
+        nm = MappingQuality::new(30, std::cmp::Ordering::Greater, Some(60));
+
+        assert!(nm.run(&*RECORD_UNAVAILABLE));
+    }
+
+    #[test]
+    fn negatif() {
+        let mut nm = MappingQuality::new(30, std::cmp::Ordering::Greater, Some(60));
+
+        assert!(!nm.run(&*RECORD));
+
+        nm = MappingQuality::new(30, std::cmp::Ordering::Less, None);
+
+        assert!(!nm.run(&*RECORD_UNAVAILABLE));
+
+        nm = MappingQuality::new(30, std::cmp::Ordering::Greater, None);
+
+        assert!(!nm.run(&*RECORD_UNAVAILABLE));
+    }
+}
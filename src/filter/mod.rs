@@ -26,6 +26,24 @@ pub trait Filter {
     fn run(&self, r: &dyn io::MappingRecord) -> bool;
 }
 
+/// Filter that need to see all mapping before taking a decision.
+///
+/// Each mapping is identified by its index in the sequence of mapping given to `add`.
+pub trait StatefulFilter {
+    fn add(&mut self, index: usize, r: &dyn io::MappingRecord);
+
+    /// Called once all mapping are added, before any call to `pass`.
+    fn finish(&mut self) {}
+
+    /// Return true if `pass` can be called just after `add` of the same mapping, input doesn't need to be read twice.
+    fn streaming(&self) -> bool {
+        false
+    }
+
+    /// Return true if mapping must be keep.
+    fn pass(&self, index: usize, r: &dyn io::MappingRecord) -> bool;
+}
+
 pub mod length;
 pub use self::length::Length;
 
@@ -35,14 +53,51 @@ pub use self::dovetails::Dovetails;
 pub mod containment;
 pub use self::containment::Containment;
 
+pub mod end_type;
+pub use self::end_type::EndType;
+
 pub mod internalmatch;
 pub use self::internalmatch::InternalMatch;
 
+pub mod overhang;
+pub use self::overhang::Overhang;
+
 pub mod samename;
 pub use self::samename::SameName;
 
 pub mod namematch;
 pub use self::namematch::NameMatch;
 
+pub mod name_list;
+pub use self::name_list::NameList;
+
+pub mod strand;
+pub use self::strand::Strand;
+
 pub mod sequence_length;
 pub use self::sequence_length::SequenceLength;
+
+pub mod path_segment;
+pub use self::path_segment::PathSegment;
+
+pub mod identity;
+pub use self::identity::Identity;
+
+pub mod mapping_quality;
+pub use self::mapping_quality::MappingQuality;
+
+pub mod tag;
+pub use self::tag::Tag;
+
+pub mod expression;
+
+pub mod rank;
+
+pub mod top_n;
+pub use self::top_n::TopN;
+
+pub mod dedup;
+pub use self::dedup::{ApproximateDedup, Dedup};
+
+pub mod coverage;
+pub use self::coverage::Coverage;
@@ -0,0 +1,193 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* project use */
+use crate::filter;
+use crate::io;
+use crate::type_def::WorkOnWichPart;
+
+/* standard use */
+use std::collections::HashSet;
+use std::io::BufRead;
+
+/// Read type reported by yacrd, first column of a yacrd report
+const YACRD_TYPES: [&str; 3] = ["Chimeric", "NotCovered", "NotBad"];
+
+pub struct NameList {
+    names: HashSet<String>,
+    part: WorkOnWichPart,
+}
+
+impl NameList {
+    pub fn new(path: &str, part: WorkOnWichPart) -> Self {
+        // a list with one short name is too small for compression detection
+        let input: Box<dyn std::io::Read> = match niffler::from_path(path) {
+            Ok((input, _)) => input,
+            Err(niffler::Error::FileTooShort) => {
+                Box::new(std::fs::File::open(path).expect("Can't open name list"))
+            }
+            Err(e) => panic!("Can't open name list: {}", e),
+        };
+
+        NameList::from_reader(std::io::BufReader::new(input), part)
+    }
+
+    /// Load names from a plain text file (one name per line), FASTA/FASTQ headers or a yacrd report
+    pub fn from_reader<R: BufRead>(reader: R, part: WorkOnWichPart) -> Self {
+        let mut names = HashSet::new();
+        let mut lines = reader
+            .lines()
+            .map(|l| l.expect("Error durring name list reading"))
+            .peekable();
+
+        while lines.peek().map_or(false, |l| l.is_empty()) {
+            lines.next();
+        }
+
+        match lines.peek().and_then(|l| l.chars().next()) {
+            Some('>') => names.extend(
+                lines
+                    .filter(|l| l.starts_with('>'))
+                    .filter_map(|l| first_word(&l[1..])),
+            ),
+            Some('@') => {
+                // empty lines are valid sequence and quality of an empty read, fastq is read by record
+                while let Some(header) = lines.next() {
+                    if let Some(header) = header.strip_prefix('@') {
+                        names.extend(first_word(header));
+
+                        // skip sequence, separator and quality
+                        lines.nth(2);
+                    }
+                }
+            }
+            _ => {
+                for line in lines.filter(|l| !l.is_empty()) {
+                    let mut fields = line.split('\t');
+                    let first = fields.next().unwrap_or("");
+
+                    if YACRD_TYPES.contains(&first) {
+                        // NotBad reads aren't flagged by yacrd
+                        if first != "NotBad" {
+                            names.extend(fields.next().map(|n| n.to_string()));
+                        }
+                    } else {
+                        names.extend(first_word(&line));
+                    }
+                }
+            }
+        }
+
+        NameList { names, part }
+    }
+}
+
+fn first_word(line: &str) -> Option<String> {
+    line.split_whitespace().next().map(|w| w.to_string())
+}
+
+impl filter::Filter for NameList {
+    fn run(&self, r: &dyn io::MappingRecord) -> bool {
+        self.part.test(r, |name, _| self.names.contains(name))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use filter::Filter;
+
+    lazy_static! {
+        static ref RECORD: io::paf::Record = {
+            io::paf::Record {
+                read_a: "read_1".to_string(),
+                length_a: 20000,
+                begin_a: 1,
+                end_a: 19999,
+                strand: '+',
+                read_b: "read_2".to_string(),
+                length_b: 20000,
+                begin_b: 1,
+                end_b: 19999,
+                nb_match_base: 500,
+                nb_base: 500,
+                mapping_quality: 255,
+                sam_field: Vec::new(),
+                position: (0, 50),
+            }
+        };
+    }
+
+    const TEXT: &[u8] = b"read_1\nread_3 comment\n\n";
+    const FASTA: &[u8] = b">read_1 comment\nACGT\nACGT\n>read_3\nACGT\n";
+    const FASTQ: &[u8] = b"@read_1 comment\nACGT\n+\n@@@@\n@read_3\nACGT\n+\n@@@@\n";
+    const YACRD: &[u8] = b"Chimeric\tread_1\t20000\t10,5000,5010\nNotBad\tread_2\t20000\t\nNotCovered\tread_3\t500\t\n";
+
+    fn load(data: &[u8], part: WorkOnWichPart) -> NameList {
+        NameList::from_reader(std::io::BufReader::new(data), part)
+    }
+
+    #[test]
+    fn formats() {
+        let expected: HashSet<String> =
+            ["read_1", "read_3"].iter().map(|s| s.to_string()).collect();
+
+        assert_eq!(load(TEXT, WorkOnWichPart::Either).names, expected);
+        assert_eq!(load(FASTA, WorkOnWichPart::Either).names, expected);
+        assert_eq!(load(FASTQ, WorkOnWichPart::Either).names, expected);
+        assert_eq!(load(YACRD, WorkOnWichPart::Either).names, expected);
+    }
+
+    #[test]
+    fn fastq_empty_read() {
+        let expected: HashSet<String> = ["read_1", "read_2", "read_3"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(
+            load(
+                b"@read_1\nACGT\n+\n@@@@\n@read_2\n\n+\n\n@read_3\nACGT\n+\n@@@@\n",
+                WorkOnWichPart::Either
+            )
+            .names,
+            expected
+        );
+    }
+
+    #[test]
+    fn positif() {
+        assert!(load(TEXT, WorkOnWichPart::Either).run(&*RECORD));
+        assert!(load(TEXT, WorkOnWichPart::Query).run(&*RECORD));
+        assert!(load(b"read_1\nread_2\n", WorkOnWichPart::Both).run(&*RECORD));
+        assert!(load(b"read_2\n", WorkOnWichPart::Target).run(&*RECORD));
+    }
+
+    #[test]
+    fn negatif() {
+        assert!(!load(TEXT, WorkOnWichPart::Target).run(&*RECORD));
+        assert!(!load(TEXT, WorkOnWichPart::Both).run(&*RECORD));
+        assert!(!load(b"read_2\n", WorkOnWichPart::Query).run(&*RECORD));
+        assert!(!load(b"read_3\n", WorkOnWichPart::Either).run(&*RECORD));
+    }
+}
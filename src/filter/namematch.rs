@@ -23,24 +23,27 @@ SOFTWARE.
 /* project use */
 use crate::filter;
 use crate::io;
+use crate::type_def::WorkOnWichPart;
 
 /* standard use */
 
 pub struct NameMatch {
     regex: regex::Regex,
+    part: WorkOnWichPart,
 }
 
 impl NameMatch {
-    pub fn new(regex: &str) -> Self {
+    pub fn new(regex: &str, part: WorkOnWichPart) -> Self {
         NameMatch {
             regex: regex::Regex::new(regex).expect("Error in regex build"),
+            part,
         }
     }
 }
 
 impl filter::Filter for NameMatch {
     fn run(&self, r: &dyn io::MappingRecord) -> bool {
-        self.regex.is_match(&r.read_a()) || self.regex.is_match(&r.read_b())
+        self.part.test(r, |name, _| self.regex.is_match(name))
     }
 }
 
@@ -73,15 +76,23 @@ mod test {
 
     #[test]
     fn positif() {
-        let nm = NameMatch::new("read_1");
+        let nm = NameMatch::new("read_1", WorkOnWichPart::Either);
 
         assert_eq!(nm.run(&*RECORD), true);
     }
 
     #[test]
     fn negatif() {
-        let nm = NameMatch::new("read_1");
+        let nm = NameMatch::new("read_1", WorkOnWichPart::Either);
 
         assert_ne!(nm.run(&*RECORD), false);
     }
+
+    #[test]
+    fn part() {
+        assert!(NameMatch::new("read_1", WorkOnWichPart::Query).run(&*RECORD));
+        assert!(!NameMatch::new("read_1", WorkOnWichPart::Target).run(&*RECORD));
+        assert!(!NameMatch::new("read_1", WorkOnWichPart::Both).run(&*RECORD));
+        assert!(NameMatch::new("read_[12]", WorkOnWichPart::Both).run(&*RECORD));
+    }
 }
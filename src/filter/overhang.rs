@@ -0,0 +1,126 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* project use */
+use crate::filter;
+use crate::io;
+
+/// Value of overhang compared to threshold.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OverhangMeasure {
+    /// overhang on left of read A in base
+    Left,
+    /// overhang on right of read A in base
+    Right,
+    /// sum of left and right overhang divide by alignment length, like in internal match
+    Ratio,
+}
+
+pub struct Overhang {
+    threshold: f64,
+    ordering: std::cmp::Ordering,
+    measure: OverhangMeasure,
+}
+
+impl Overhang {
+    pub fn new(threshold: f64, ord: std::cmp::Ordering, measure: OverhangMeasure) -> Self {
+        Overhang {
+            threshold,
+            ordering: ord,
+            measure,
+        }
+    }
+}
+
+impl filter::Filter for Overhang {
+    fn run(&self, r: &dyn io::MappingRecord) -> bool {
+        let (left, right) = filter::internalmatch::overhangs(r);
+
+        let value = match self.measure {
+            OverhangMeasure::Left => left as f64,
+            OverhangMeasure::Right => right as f64,
+            OverhangMeasure::Ratio => {
+                (left + right) as f64 / filter::internalmatch::mapping_length(r) as f64
+            }
+        };
+
+        value.partial_cmp(&self.threshold) == Some(self.ordering)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use filter::Filter;
+
+    lazy_static! {
+        static ref RECORD: io::paf::Record = {
+            io::paf::Record {
+                read_a: "read_1".to_string(),
+                length_a: 20000,
+                begin_a: 200,
+                end_a: 10000,
+                strand: '+',
+                read_b: "read_2".to_string(),
+                length_b: 20000,
+                begin_b: 1000,
+                end_b: 10800,
+                nb_match_base: 9800,
+                nb_base: 9800,
+                mapping_quality: 255,
+                sam_field: Vec::new(),
+                position: (0, 50),
+            }
+        };
+        static ref RECORD_REV: io::paf::Record = {
+            io::paf::Record {
+                strand: '-',
+                ..RECORD.clone()
+            }
+        };
+    }
+
+    #[test]
+    fn overhangs() {
+        assert_eq!(filter::internalmatch::overhangs(&*RECORD), (200, 9200));
+        assert_eq!(filter::internalmatch::overhangs(&*RECORD_REV), (200, 1000));
+    }
+
+    #[test]
+    fn positif() {
+        let less = std::cmp::Ordering::Less;
+
+        assert!(Overhang::new(201.0, less, OverhangMeasure::Left).run(&*RECORD));
+        assert!(Overhang::new(1001.0, less, OverhangMeasure::Right).run(&*RECORD_REV));
+        assert!(Overhang::new(0.2, less, OverhangMeasure::Ratio).run(&*RECORD_REV));
+    }
+
+    #[test]
+    fn negatif() {
+        let less = std::cmp::Ordering::Less;
+
+        assert!(!Overhang::new(1001.0, less, OverhangMeasure::Right).run(&*RECORD));
+        assert!(!Overhang::new(200.0, less, OverhangMeasure::Left).run(&*RECORD));
+        assert!(!Overhang::new(0.2, less, OverhangMeasure::Ratio).run(&*RECORD));
+    }
+}
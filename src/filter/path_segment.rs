@@ -0,0 +1,90 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* project use */
+use crate::filter;
+use crate::io;
+
+/* standard use */
+use std::collections::HashSet;
+
+pub struct PathSegment {
+    segments: HashSet<String>,
+}
+
+impl PathSegment {
+    pub fn new<'a>(segments: impl Iterator<Item = &'a str>) -> Self {
+        PathSegment {
+            segments: segments.map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+impl filter::Filter for PathSegment {
+    fn run(&self, r: &dyn io::MappingRecord) -> bool {
+        io::gaf::path_segments(&r.read_b())
+            .iter()
+            .any(|(segment, _)| self.segments.contains(segment))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use filter::Filter;
+
+    lazy_static! {
+        static ref RECORD: io::gaf::Record = {
+            io::gaf::Record {
+                read_a: "read_1".to_string(),
+                length_a: 5000,
+                begin_a: 0,
+                end_a: 5000,
+                strand: '+',
+                path: ">s1<s2>s3".to_string(),
+                path_length: 20000,
+                path_begin: 5000,
+                path_end: 10000,
+                nb_match_base: 500,
+                nb_base: 500,
+                mapping_quality: 255,
+                sam_field: Vec::new(),
+                position: (0, 50),
+            }
+        };
+    }
+
+    #[test]
+    fn positif() {
+        let nm = PathSegment::new("s2,s5".split(','));
+
+        assert!(nm.run(&*RECORD));
+    }
+
+    #[test]
+    fn negatif() {
+        let nm = PathSegment::new("s4,s5".split(','));
+
+        assert!(!nm.run(&*RECORD));
+    }
+}
@@ -0,0 +1,137 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* project use */
+use crate::filter;
+use crate::io;
+
+/// Criterion used to rank mapping.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Criterion {
+    Length,
+    Identity,
+    Matches,
+}
+
+impl From<&str> for Criterion {
+    fn from(criterion: &str) -> Self {
+        match criterion {
+            "identity" => Criterion::Identity,
+            "matches" => Criterion::Matches,
+            _ => Criterion::Length,
+        }
+    }
+}
+
+impl Criterion {
+    pub fn score(&self, r: &dyn io::MappingRecord) -> f64 {
+        match self {
+            Criterion::Length => r.length() as f64,
+            Criterion::Identity => filter::Identity::identity(r),
+            Criterion::Matches => r.matches() as f64,
+        }
+    }
+}
+
+/// Mapping score, on equal score first mapping is the best.
+#[derive(Debug, Clone, Copy)]
+pub struct Candidate {
+    pub score: f64,
+    pub index: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.score
+            .partial_cmp(&other.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn criterion() {
+        assert_eq!(Criterion::from("length"), Criterion::Length);
+        assert_eq!(Criterion::from("identity"), Criterion::Identity);
+        assert_eq!(Criterion::from("matches"), Criterion::Matches);
+    }
+
+    #[test]
+    fn identity_tag() {
+        let record = io::paf::Record {
+            read_a: "read_1".to_string(),
+            length_a: 5000,
+            begin_a: 0,
+            end_a: 5000,
+            strand: '+',
+            read_b: "read_2".to_string(),
+            length_b: 20000,
+            begin_b: 5000,
+            end_b: 10000,
+            nb_match_base: 4500,
+            nb_base: 5000,
+            mapping_quality: 255,
+            sam_field: vec!["dv:f:0.02".to_string()],
+            position: (0, 50),
+        };
+
+        assert_eq!(Criterion::Identity.score(&record), 0.98);
+    }
+
+    #[test]
+    fn ordering() {
+        let best = Candidate {
+            score: 1.0,
+            index: 2,
+        };
+        let first = Candidate {
+            score: 0.5,
+            index: 0,
+        };
+        let second = Candidate {
+            score: 0.5,
+            index: 1,
+        };
+
+        assert!(best > first);
+        assert!(first > second);
+    }
+}
@@ -23,25 +23,29 @@ SOFTWARE.
 /* project use */
 use crate::filter;
 use crate::io;
+use crate::type_def::WorkOnWichPart;
 
 pub struct SequenceLength {
     length_threshold: u64,
     ordering: std::cmp::Ordering,
+    part: WorkOnWichPart,
 }
 
 impl SequenceLength {
-    pub fn new(length_threshold: u64, ord: std::cmp::Ordering) -> Self {
+    pub fn new(length_threshold: u64, ord: std::cmp::Ordering, part: WorkOnWichPart) -> Self {
         SequenceLength {
             length_threshold,
             ordering: ord,
+            part,
         }
     }
 }
 
 impl filter::Filter for SequenceLength {
     fn run(&self, r: &dyn io::MappingRecord) -> bool {
-        r.length_a().cmp(&self.length_threshold) == self.ordering
-            || r.length_b().cmp(&self.length_threshold) == self.ordering
+        self.part.test(r, |_, length| {
+            length.cmp(&self.length_threshold) == self.ordering
+        })
     }
 }
 
@@ -74,23 +78,36 @@ mod test {
 
     #[test]
     fn positif() {
-        let mut nm = SequenceLength::new(5001, std::cmp::Ordering::Less);
+        let mut nm = SequenceLength::new(5001, std::cmp::Ordering::Less, WorkOnWichPart::Either);
 
         assert_eq!(nm.run(&*RECORD), true);
 
-        nm = SequenceLength::new(20001, std::cmp::Ordering::Greater);
+        nm = SequenceLength::new(20001, std::cmp::Ordering::Greater, WorkOnWichPart::Either);
 
         assert_eq!(nm.run(&*RECORD), false);
     }
 
     #[test]
     fn negatif() {
-        let mut nm = SequenceLength::new(5001, std::cmp::Ordering::Less);
+        let mut nm = SequenceLength::new(5001, std::cmp::Ordering::Less, WorkOnWichPart::Either);
 
         assert_ne!(nm.run(&*RECORD), false);
 
-        nm = SequenceLength::new(20001, std::cmp::Ordering::Greater);
+        nm = SequenceLength::new(20001, std::cmp::Ordering::Greater, WorkOnWichPart::Either);
 
         assert_ne!(nm.run(&*RECORD), true);
     }
+
+    #[test]
+    fn part() {
+        let lower = |part| SequenceLength::new(5001, std::cmp::Ordering::Less, part);
+
+        assert!(lower(WorkOnWichPart::Query).run(&*RECORD));
+        assert!(!lower(WorkOnWichPart::Target).run(&*RECORD));
+        assert!(!lower(WorkOnWichPart::Both).run(&*RECORD));
+        assert!(
+            SequenceLength::new(30000, std::cmp::Ordering::Less, WorkOnWichPart::Both)
+                .run(&*RECORD)
+        );
+    }
 }
@@ -0,0 +1,175 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* project use */
+use crate::filter;
+use crate::io;
+
+/* standard use */
+use std::cmp::Ordering;
+
+enum Test {
+    Exist,
+    Equal(String),
+    NotEqual(String),
+    Regex(regex::Regex),
+    Compare(f64, Ordering, bool),
+}
+
+pub struct Tag {
+    name: String,
+    test: Test,
+}
+
+impl Tag {
+    /// Build filter from a test description `NAME[OPERATOR VALUE]`.
+    ///
+    /// Without operator tag must exist, operators are `=`, `!=`, `~` (regex), `<`, `<=`, `>` and `>=`.
+    pub fn new(description: &str) -> Self {
+        let begin = match description.find(|c| "=!<>~".contains(c)) {
+            Some(begin) => begin,
+            None => {
+                return Tag {
+                    name: description.to_string(),
+                    test: Test::Exist,
+                }
+            }
+        };
+
+        let name = description[..begin].to_string();
+        let rest = &description[begin..];
+        let (operator, value) = ["==", "!=", "<=", ">=", "=", "~", "<", ">"]
+            .iter()
+            .find_map(|op| rest.strip_prefix(op).map(|value| (*op, value)))
+            .expect("Error in tag filter operator");
+
+        let test = match operator {
+            "=" | "==" => Test::Equal(value.to_string()),
+            "!=" => Test::NotEqual(value.to_string()),
+            "~" => Test::Regex(regex::Regex::new(value).expect("Error in regex build")),
+            _ => {
+                let threshold = value
+                    .parse::<f64>()
+                    .expect("Error in tag filter numeric value");
+                match operator {
+                    "<" => Test::Compare(threshold, Ordering::Less, false),
+                    "<=" => Test::Compare(threshold, Ordering::Less, true),
+                    ">" => Test::Compare(threshold, Ordering::Greater, false),
+                    _ => Test::Compare(threshold, Ordering::Greater, true),
+                }
+            }
+        };
+
+        Tag { name, test }
+    }
+}
+
+fn equal(value: &io::tag::Value, expected: &str) -> bool {
+    match (value.as_f64(), expected.parse::<f64>()) {
+        (Some(v), Ok(e)) => v == e,
+        _ => value.to_string() == expected,
+    }
+}
+
+impl filter::Filter for Tag {
+    fn run(&self, r: &dyn io::MappingRecord) -> bool {
+        let value = match r.tag(&self.name) {
+            Some(value) => value,
+            None => return false,
+        };
+
+        match &self.test {
+            Test::Exist => true,
+            Test::Equal(expected) => equal(&value, expected),
+            Test::NotEqual(expected) => !equal(&value, expected),
+            Test::Regex(regex) => regex.is_match(&value.to_string()),
+            Test::Compare(threshold, ordering, or_equal) => match value.as_f64() {
+                Some(v) => match v.partial_cmp(threshold) {
+                    Some(Ordering::Equal) => *or_equal,
+                    Some(o) => o == *ordering,
+                    None => false,
+                },
+                None => false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use filter::Filter;
+
+    lazy_static! {
+        static ref RECORD: io::paf::Record = {
+            io::paf::Record {
+                read_a: "read_1".to_string(),
+                length_a: 5000,
+                begin_a: 0,
+                end_a: 5000,
+                strand: '+',
+                read_b: "read_2".to_string(),
+                length_b: 20000,
+                begin_b: 5000,
+                end_b: 10000,
+                nb_match_base: 500,
+                nb_base: 500,
+                mapping_quality: 255,
+                sam_field: vec![
+                    "tp:A:P".to_string(),
+                    "cm:i:12".to_string(),
+                    "s1:i:250".to_string(),
+                    "dv:f:0.0125".to_string(),
+                ],
+                position: (0, 50),
+            }
+        };
+    }
+
+    #[test]
+    fn positif() {
+        for description in &[
+            "tp",
+            "tp=P",
+            "tp==P",
+            "tp!=S",
+            "tp~^[PS]$",
+            "cm>=12",
+            "cm<13",
+            "s1>200",
+            "dv<=0.0125",
+            "dv=0.0125",
+        ] {
+            assert!(Tag::new(description).run(&*RECORD), "{}", description);
+        }
+    }
+
+    #[test]
+    fn negatif() {
+        for description in &[
+            "NM", "tp=S", "tp!=P", "tp~^S", "cm>12", "cm<12", "s1<=200", "NM<10", "tp>1",
+        ] {
+            assert!(!Tag::new(description).run(&*RECORD), "{}", description);
+        }
+    }
+}
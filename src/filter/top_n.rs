@@ -0,0 +1,232 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* project use */
+use crate::filter;
+use crate::io;
+use filter::rank::{Candidate, Criterion};
+use io::gfa::OverlapEnd;
+
+/* standard use */
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// Keep the N best mapping of each read, or of each read end. A mapping is keep if it's among the best of one of its two reads.
+///
+/// By end, containments and internal matches extend no read end and are never keep.
+///
+/// Only score and index of the N best mapping of each read are stored, index of selected mapping are used during second read of input.
+pub struct TopN {
+    n: usize,
+    criterion: Criterion,
+    per_end: bool,
+    internal_threshold: f64,
+    max_overhang: u64,
+    // key is read name and read end (true for suffix) if per_end is set
+    best: HashMap<(String, Option<bool>), BinaryHeap<Reverse<Candidate>>>,
+    selected: HashSet<usize>,
+}
+
+impl TopN {
+    pub fn new(
+        n: usize,
+        criterion: Criterion,
+        per_end: bool,
+        internal_threshold: f64,
+        max_overhang: u64,
+    ) -> Self {
+        TopN {
+            n,
+            criterion,
+            per_end,
+            internal_threshold,
+            max_overhang,
+            best: HashMap::new(),
+            selected: HashSet::new(),
+        }
+    }
+
+    fn push(&mut self, key: (String, Option<bool>), candidate: Candidate) {
+        let heap = self.best.entry(key).or_default();
+
+        heap.push(Reverse(candidate));
+        if heap.len() > self.n {
+            heap.pop();
+        }
+    }
+}
+
+impl filter::StatefulFilter for TopN {
+    fn add(&mut self, index: usize, r: &dyn io::MappingRecord) {
+        let candidate = Candidate {
+            score: self.criterion.score(r),
+            index,
+        };
+
+        let (end_a, end_b) = if self.per_end {
+            match OverlapEnd::classify(r, self.internal_threshold, self.max_overhang) {
+                Some(end) => (
+                    Some(matches!(
+                        end,
+                        OverlapEnd::SuffixPrefix | OverlapEnd::SuffixSuffix
+                    )),
+                    Some(matches!(
+                        end,
+                        OverlapEnd::PrefixSuffix | OverlapEnd::SuffixSuffix
+                    )),
+                ),
+                None => return,
+            }
+        } else {
+            (None, None)
+        };
+
+        self.push((r.read_a(), end_a), candidate);
+        self.push((r.read_b(), end_b), candidate);
+    }
+
+    fn finish(&mut self) {
+        self.selected = self
+            .best
+            .drain()
+            .flat_map(|(_, heap)| heap.into_iter().map(|Reverse(c)| c.index))
+            .collect();
+    }
+
+    fn pass(&self, index: usize, _r: &dyn io::MappingRecord) -> bool {
+        self.selected.contains(&index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+    use filter::StatefulFilter;
+
+    fn record(
+        read_a: &str,
+        begin_a: u64,
+        end_a: u64,
+        read_b: &str,
+        begin_b: u64,
+        end_b: u64,
+        nb_match_base: u64,
+    ) -> io::paf::Record {
+        io::paf::Record {
+            read_a: read_a.to_string(),
+            length_a: 20000,
+            begin_a,
+            end_a,
+            strand: '+',
+            read_b: read_b.to_string(),
+            length_b: 20000,
+            begin_b,
+            end_b,
+            nb_match_base,
+            nb_base: end_a - begin_a,
+            mapping_quality: 255,
+            sam_field: Vec::new(),
+            position: (0, 50),
+        }
+    }
+
+    lazy_static! {
+        static ref RECORDS: Vec<io::paf::Record> = vec![
+            // read_1 suffix, read_2 prefix
+            record("read_1", 15000, 20000, "read_2", 0, 5000, 4500),
+            // read_1 suffix, read_3 prefix
+            record("read_1", 10000, 20000, "read_3", 0, 10000, 8000),
+            // read_2 prefix, read_3 suffix
+            record("read_2", 0, 2000, "read_3", 18000, 20000, 2000),
+            // read_4 prefix, read_2 suffix
+            record("read_4", 0, 1000, "read_2", 19000, 20000, 900),
+            // read_2 prefix, read_5 suffix
+            record("read_2", 0, 6000, "read_5", 14000, 20000, 6000),
+        ];
+    }
+
+    fn selected(mut top: TopN) -> Vec<bool> {
+        for (i, r) in RECORDS.iter().enumerate() {
+            top.add(i, r);
+        }
+        top.finish();
+
+        RECORDS
+            .iter()
+            .enumerate()
+            .map(|(i, r)| top.pass(i, r))
+            .collect()
+    }
+
+    #[test]
+    fn per_read() {
+        assert_eq!(
+            selected(TopN::new(1, Criterion::Length, false, 0.8, 1000)),
+            vec![false, true, false, true, true]
+        );
+        // on equal identity first mapping is keep
+        assert_eq!(
+            selected(TopN::new(1, Criterion::Identity, false, 0.8, 1000)),
+            vec![true, false, true, true, true]
+        );
+        assert_eq!(
+            selected(TopN::new(2, Criterion::Length, false, 0.8, 1000)),
+            vec![true, true, true, true, true]
+        );
+        assert_eq!(
+            selected(TopN::new(0, Criterion::Length, false, 0.8, 1000)),
+            vec![false, false, false, false, false]
+        );
+    }
+
+    #[test]
+    fn per_end() {
+        // read_2 and read_3 use different end in their mapping
+        assert_eq!(
+            selected(TopN::new(1, Criterion::Length, true, 0.8, 1000)),
+            vec![false, true, true, true, true]
+        );
+        assert_eq!(
+            selected(TopN::new(1, Criterion::Matches, true, 0.8, 1000)),
+            vec![false, true, true, true, true]
+        );
+    }
+
+    #[test]
+    fn per_end_containment() {
+        // read_6 is contained in read_1, it's the longest mapping of read_1
+        let containment = io::paf::Record {
+            length_b: 8000,
+            ..record("read_1", 5000, 13000, "read_6", 0, 8000, 8000)
+        };
+        let dovetail = record("read_1", 15000, 20000, "read_2", 0, 5000, 4500);
+
+        let mut top = TopN::new(1, Criterion::Length, true, 0.8, 1000);
+        top.add(0, &containment);
+        top.add(1, &dovetail);
+        top.finish();
+
+        assert!(!top.pass(0, &containment));
+        assert!(top.pass(1, &dovetail));
+    }
+}
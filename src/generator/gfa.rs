@@ -28,21 +28,52 @@ SOFTWARE.
 use crate::generator;
 use crate::io;
 
+/// Graph operations and output format of gfa modifier
+#[derive(Debug, Clone, Default)]
+pub struct GfaOutput {
+    pub gfa2: bool,
+    pub reads: Option<String>,
+    pub transitive_reduction: Option<u64>,
+    /// maximal number of reads and length of tips
+    pub trim_tips: Option<(usize, u64)>,
+    /// maximal length of bubbles
+    pub pop_bubbles: Option<u64>,
+    pub unitig: bool,
+    pub unitig_fasta: Option<String>,
+}
+
 pub struct Gfa1 {
     gfa_path: String,
+    output: GfaOutput,
     gfa_object: io::gfa::Gfa1,
 }
 
 impl Gfa1 {
-    pub fn new(
-        gfa_path: String,
-        keep_internal: bool,
-        keep_containment: bool,
-        internal_threshold: f64,
-    ) -> Self {
+    pub fn new(gfa_path: String, output: GfaOutput, gfa_object: io::gfa::Gfa1) -> Self {
         Gfa1 {
             gfa_path,
-            gfa_object: io::gfa::Gfa1::new(keep_internal, keep_containment, internal_threshold),
+            output,
+            gfa_object,
+        }
+    }
+
+    fn write_unitigs<W: std::io::Write>(&mut self, writer: &mut W) {
+        let graph = self.gfa_object.unitig_graph();
+        eprintln!("{} unitigs built", graph.unitigs.len());
+
+        let sequences = self
+            .output
+            .reads
+            .as_deref()
+            .map(|path| io::gfa::unitig::read_sequences(path, &graph.used_parts()));
+
+        graph.write(writer, sequences.as_ref());
+
+        if let (Some(path), Some(sequences)) = (&self.output.unitig_fasta, &sequences) {
+            let mut fasta = std::io::BufWriter::new(
+                std::fs::File::create(path).expect("Can't create unitig fasta output"),
+            );
+            graph.write_fasta(&mut fasta, sequences);
         }
     }
 }
@@ -56,6 +87,36 @@ impl generator::Modifier for Gfa1 {
         let mut writer = std::io::BufWriter::new(
             std::fs::File::create(&self.gfa_path).expect("Can't create gfa ou"),
         );
-        self.gfa_object.write(&mut writer);
+
+        if let Some(fuzz) = self.output.transitive_reduction {
+            eprintln!(
+                "Transitive reduction removed {} edges",
+                self.gfa_object.transitive_reduction(fuzz)
+            );
+        }
+
+        if let Some((max_reads, max_length)) = self.output.trim_tips {
+            eprintln!(
+                "Tip trimming removed {} reads",
+                self.gfa_object.trim_tips(max_reads, max_length)
+            );
+        }
+
+        if let Some(max_length) = self.output.pop_bubbles {
+            eprintln!(
+                "Bubble popping popped {} bubbles",
+                self.gfa_object.pop_bubbles(max_length)
+            );
+        }
+
+        if self.output.unitig {
+            self.write_unitigs(&mut writer);
+        } else if self.output.gfa2 {
+            self.gfa_object
+                .write_gfa2(&mut writer, self.output.reads.as_deref());
+        } else {
+            self.gfa_object
+                .write(&mut writer, self.output.reads.as_deref());
+        }
     }
 }
@@ -86,7 +86,7 @@ impl generator::Modifier for Indexing {
         }
 
         match self.index_type {
-            WorkOnWichPart::Both => self.run_both(r),
+            WorkOnWichPart::Both | WorkOnWichPart::Either => self.run_both(r),
             WorkOnWichPart::Query => self.run_query(r),
             WorkOnWichPart::Target => self.run_target(r),
         }
@@ -35,4 +35,4 @@ pub mod indexing;
 pub use self::indexing::Indexing;
 
 pub mod gfa;
-pub use self::gfa::Gfa1;
+pub use self::gfa::{Gfa1, GfaOutput};
@@ -0,0 +1,106 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* standard use */
+use std::io::BufRead;
+
+/// Call `f` with name (first word of header) and sequence of each record of a fasta or fastq file, only one record is stored in memory.
+pub fn for_each<R: BufRead, F: FnMut(&str, &[u8])>(reader: R, mut f: F) -> std::io::Result<()> {
+    let mut lines = reader.lines();
+
+    let mut name: Option<String> = None;
+    let mut sequence = Vec::new();
+
+    while let Some(line) = lines.next() {
+        let line = line?;
+
+        if let Some(header) = line.strip_prefix('>') {
+            if let Some(n) = name.take() {
+                f(&n, &sequence);
+            }
+
+            name = Some(header.split_whitespace().next().unwrap_or("").to_string());
+            sequence.clear();
+        } else if let Some(header) = line.strip_prefix('@') {
+            let header = header.split_whitespace().next().unwrap_or("").to_string();
+            let sequence = lines.next().transpose()?.unwrap_or_default();
+
+            // skip separator and quality
+            lines.next().transpose()?;
+            lines.next().transpose()?;
+
+            f(&header, sequence.as_bytes());
+        } else if name.is_some() {
+            sequence.extend_from_slice(line.trim_end().as_bytes());
+        }
+    }
+
+    if let Some(n) = name {
+        f(&n, &sequence);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    fn read(data: &[u8]) -> Vec<(String, String)> {
+        let mut records = Vec::new();
+
+        for_each(std::io::BufReader::new(data), |name, seq| {
+            records.push((name.to_string(), String::from_utf8(seq.to_vec()).unwrap()))
+        })
+        .unwrap();
+
+        records
+    }
+
+    #[test]
+    fn fasta() {
+        assert_eq!(
+            read(b">read_1 comment\nACGT\nTT\n>read_2\nGGG\n"),
+            vec![
+                ("read_1".to_string(), "ACGTTT".to_string()),
+                ("read_2".to_string(), "GGG".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn fastq() {
+        assert_eq!(
+            read(b"@read_1 comment\nACGT\n+\n@@@@\n@read_2\nGGG\n+read_2\n!!!\n"),
+            vec![
+                ("read_1".to_string(), "ACGT".to_string()),
+                ("read_2".to_string(), "GGG".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn empty() {
+        assert!(read(b"").is_empty());
+    }
+}
@@ -0,0 +1,457 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* local use */
+use crate::io;
+
+/* standard use */
+use std::cmp::min;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Record {
+    pub read_a: String,
+    pub length_a: u64,
+    pub begin_a: u64,
+    pub end_a: u64,
+    pub strand: char,
+    pub path: String,
+    pub path_length: u64,
+    pub path_begin: u64,
+    pub path_end: u64,
+    pub nb_match_base: u64,
+    pub nb_base: u64,
+    pub mapping_quality: u64,
+    pub sam_field: Vec<String>,
+    pub position: (u64, u64),
+}
+
+impl io::MappingRecord for Record {
+    fn read_a(&self) -> String {
+        self.read_a.clone()
+    }
+
+    fn length_a(&self) -> u64 {
+        self.length_a
+    }
+
+    fn begin_a(&self) -> u64 {
+        self.begin_a
+    }
+
+    fn end_a(&self) -> u64 {
+        self.end_a
+    }
+
+    fn strand(&self) -> char {
+        self.strand
+    }
+
+    fn read_b(&self) -> String {
+        self.path.clone()
+    }
+
+    fn length_b(&self) -> u64 {
+        self.path_length
+    }
+
+    fn begin_b(&self) -> u64 {
+        self.path_begin
+    }
+
+    fn end_b(&self) -> u64 {
+        self.path_end
+    }
+
+    fn position(&self) -> (u64, u64) {
+        self.position
+    }
+
+    fn set_position(&mut self, p: (u64, u64)) {
+        self.position = p;
+    }
+
+    fn length(&self) -> u64 {
+        min(self.end_a - self.begin_a, self.path_end - self.path_begin)
+    }
+
+    fn matches(&self) -> u64 {
+        self.nb_match_base
+    }
+
+    fn block_length(&self) -> u64 {
+        self.nb_base
+    }
+
+    fn identity(&self) -> f64 {
+        if self.nb_base == 0 {
+            0.0
+        } else {
+            self.nb_match_base as f64 / self.nb_base as f64
+        }
+    }
+
+    fn mapping_quality(&self) -> Option<u64> {
+        Some(self.mapping_quality)
+    }
+
+    fn tag(&self, name: &str) -> Option<io::tag::Value> {
+        io::tag::find(&self.sam_field, name)
+    }
+
+    fn len_to_end_a(&self) -> u64 {
+        self.length_a - self.end_a
+    }
+
+    fn len_to_end_b(&self) -> u64 {
+        self.path_length - self.path_end
+    }
+
+    fn set_read_a(&mut self, new_name: String) {
+        self.read_a = new_name;
+    }
+    fn set_read_b(&mut self, new_name: String) {
+        self.path = new_name;
+    }
+}
+
+/// Split a gaf path in oriented segments traversed by the alignment, a path without orientation is a single forward segment.
+pub fn path_segments(path: &str) -> Vec<(String, char)> {
+    if !path.starts_with('>') && !path.starts_with('<') {
+        return vec![(path.to_string(), '+')];
+    }
+
+    let mut segments: Vec<(String, char)> = Vec::new();
+    for c in path.chars() {
+        match c {
+            '>' => segments.push((String::new(), '+')),
+            '<' => segments.push((String::new(), '-')),
+            _ => segments.last_mut().unwrap().0.push(c),
+        }
+    }
+
+    segments
+}
+
+impl From<&Record> for Record {
+    fn from(record: &Record) -> Self {
+        record.clone()
+    }
+}
+
+/// Build a gaf record from a paf record, target name is used as path.
+impl From<&io::paf::Record> for Record {
+    fn from(record: &io::paf::Record) -> Self {
+        Record {
+            read_a: record.read_a.clone(),
+            length_a: record.length_a,
+            begin_a: record.begin_a,
+            end_a: record.end_a,
+            strand: record.strand,
+            path: record.read_b.clone(),
+            path_length: record.length_b,
+            path_begin: record.begin_b,
+            path_end: record.end_b,
+            nb_match_base: record.nb_match_base,
+            nb_base: record.nb_base,
+            mapping_quality: record.mapping_quality,
+            sam_field: record.sam_field.clone(),
+            position: record.position,
+        }
+    }
+}
+
+impl From<&io::m4::Record> for Record {
+    fn from(record: &io::m4::Record) -> Self {
+        Record::from(&io::paf::Record::from(record))
+    }
+}
+
+impl From<&io::sam::Record> for Record {
+    fn from(record: &io::sam::Record) -> Self {
+        Record::from(&io::paf::Record::from(record))
+    }
+}
+
+impl From<&io::las::Record> for Record {
+    fn from(record: &io::las::Record) -> Self {
+        Record::from(&io::paf::Record::from(record))
+    }
+}
+
+type RecordInner = (
+    String,
+    u64,
+    u64,
+    u64,
+    char,
+    String,
+    u64,
+    u64,
+    u64,
+    u64,
+    u64,
+    Vec<String>,
+);
+
+fn invalid_data(message: String) -> csv::Error {
+    csv::Error::from(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        message,
+    ))
+}
+
+pub struct Records<'a, R: 'a + std::io::Read> {
+    inner: csv::DeserializeRecordsIter<'a, R, RecordInner>,
+}
+
+impl<'a, R: std::io::Read> Iterator for Records<'a, R> {
+    type Item = csv::Result<Record>;
+
+    fn next(&mut self) -> Option<csv::Result<Record>> {
+        let position = self.inner.reader().position().byte();
+        self.inner.next().map(|res| {
+            res.and_then(
+                |(
+                    read_a,
+                    length_a,
+                    begin_a,
+                    end_a,
+                    strand,
+                    path,
+                    path_length,
+                    path_begin,
+                    path_end,
+                    nb_match_base,
+                    nb_base,
+                    mapping_quality_and_sam,
+                )| {
+                    // a missing mapping quality is written `*` or 255
+                    let mapping_quality = match mapping_quality_and_sam.first().map(|v| v.as_str())
+                    {
+                        Some("*") => 255,
+                        Some(value) => value.parse::<u64>().map_err(|_| {
+                            invalid_data(format!("Mapping quality {} isn't an integer", value))
+                        })?,
+                        None => return Err(invalid_data("Mapping quality is missing".to_string())),
+                    };
+
+                    let sam_field = if mapping_quality_and_sam.len() > 1 {
+                        mapping_quality_and_sam[1..].to_vec()
+                    } else {
+                        Vec::new()
+                    };
+
+                    let new_position = self.inner.reader().position().byte();
+                    Ok(Record {
+                        read_a,
+                        length_a,
+                        begin_a,
+                        end_a,
+                        strand,
+                        path,
+                        path_length,
+                        path_begin,
+                        path_end,
+                        nb_match_base,
+                        nb_base,
+                        mapping_quality,
+                        sam_field,
+                        position: (position, new_position),
+                    })
+                },
+            )
+        })
+    }
+}
+
+pub struct Reader<R: std::io::Read> {
+    inner: csv::Reader<R>,
+}
+
+impl<R: std::io::Read> Reader<R> {
+    pub fn new(reader: R) -> Self {
+        Reader {
+            inner: csv::ReaderBuilder::new()
+                .delimiter(b'\t')
+                .has_headers(false)
+                .flexible(true)
+                .from_reader(reader),
+        }
+    }
+
+    /// Iterate over all records.
+    pub fn records(&mut self) -> Records<'_, R> {
+        Records {
+            inner: self.inner.deserialize(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Writer<W: std::io::Write> {
+    inner: csv::Writer<W>,
+}
+
+impl<W: std::io::Write> Writer<W> {
+    /// Write to a given writer.
+    pub fn new(writer: W) -> Self {
+        Writer {
+            inner: csv::WriterBuilder::new()
+                .delimiter(b'\t')
+                .has_headers(false)
+                .flexible(true)
+                .from_writer(writer),
+        }
+    }
+
+    /// Write a given gaf record.
+    pub fn write(&mut self, record: &Record) -> csv::Result<u64> {
+        let buffer: Vec<u8> = Vec::new();
+        let mut wrapper = csv::WriterBuilder::new()
+            .delimiter(b'\t')
+            .has_headers(false)
+            .flexible(true)
+            .from_writer(buffer);
+
+        wrapper.serialize((
+            &record.read_a,
+            record.length_a,
+            record.begin_a,
+            record.end_a,
+            record.strand,
+            &record.path,
+            record.path_length,
+            record.path_begin,
+            record.path_end,
+            record.nb_match_base,
+            record.nb_base,
+            record.mapping_quality,
+            &record.sam_field,
+        ))?;
+
+        let nb_bytes = wrapper.into_inner().unwrap().len() as u64;
+
+        self.inner.serialize((
+            &record.read_a,
+            record.length_a,
+            record.begin_a,
+            record.end_a,
+            record.strand,
+            &record.path,
+            record.path_length,
+            record.path_begin,
+            record.path_end,
+            record.nb_match_base,
+            record.nb_base,
+            record.mapping_quality,
+            &record.sam_field,
+        ))?;
+
+        Ok(nb_bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    const GAF_FILE: &[u8] =
+        b"1\t12000\t20\t4500\t+\t>s1<s2>s3\t10000\t5500\t10000\t4500\t4500\t60\ttp:A:P
+1\t12000\t5500\t10000\t-\ts4\t10000\t0\t4500\t4500\t4500\t255
+";
+
+    const READ_A: &[&str; 2] = &["1", "1"];
+    const LENGTH_A: &[u64; 2] = &[12000, 12000];
+    const BEGIN_A: &[u64; 2] = &[20, 5500];
+    const END_A: &[u64; 2] = &[4500, 10000];
+    const STRAND: &[char; 2] = &['+', '-'];
+    const PATH: &[&str; 2] = &[">s1<s2>s3", "s4"];
+    const PATH_LENGTH: &[u64; 2] = &[10000, 10000];
+    const PATH_BEGIN: &[u64; 2] = &[5500, 0];
+    const PATH_END: &[u64; 2] = &[10000, 4500];
+    const MAPPING_QUALITY: &[u64; 2] = &[60, 255];
+
+    #[test]
+    fn read() {
+        let mut reader = Reader::new(GAF_FILE);
+
+        for (i, r) in reader.records().enumerate() {
+            let record = r.unwrap();
+
+            assert_eq!(record.read_a, READ_A[i]);
+            assert_eq!(record.length_a, LENGTH_A[i]);
+            assert_eq!(record.begin_a, BEGIN_A[i]);
+            assert_eq!(record.end_a, END_A[i]);
+            assert_eq!(record.strand, STRAND[i]);
+            assert_eq!(record.path, PATH[i]);
+            assert_eq!(record.path_length, PATH_LENGTH[i]);
+            assert_eq!(record.path_begin, PATH_BEGIN[i]);
+            assert_eq!(record.path_end, PATH_END[i]);
+            assert_eq!(record.mapping_quality, MAPPING_QUALITY[i]);
+        }
+    }
+
+    #[test]
+    fn read_mapping_quality() {
+        let mut reader = Reader::new(
+            &b"1\t12000\t20\t4500\t+\ts1\t10000\t5500\t10000\t4500\t4500\t*
+1\t12000\t20\t4500\t+\ts1\t10000\t5500\t10000\t4500\t4500\tbad
+"[..],
+        );
+        let mut records = reader.records();
+
+        assert_eq!(records.next().unwrap().unwrap().mapping_quality, 255);
+        assert!(records.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn segments() {
+        let mut reader = Reader::new(GAF_FILE);
+        let records: Vec<Record> = reader.records().map(|r| r.unwrap()).collect();
+
+        assert_eq!(
+            path_segments(&records[0].path),
+            vec![
+                ("s1".to_string(), '+'),
+                ("s2".to_string(), '-'),
+                ("s3".to_string(), '+')
+            ]
+        );
+        assert_eq!(
+            path_segments(&records[1].path),
+            vec![("s4".to_string(), '+')]
+        );
+    }
+
+    #[test]
+    fn write() {
+        let mut reader = Reader::new(GAF_FILE);
+        let mut writer = Writer::new(vec![]);
+        for r in reader.records() {
+            writer
+                .write(&r.expect("Error reading record"))
+                .expect("Error writing record");
+        }
+        assert_eq!(writer.inner.into_inner().unwrap(), GAF_FILE);
+    }
+}
@@ -25,19 +25,69 @@ use std::clone::Clone;
 use std::collections::{HashMap, HashSet};
 
 /* crate use */
-use petgraph::graph::NodeIndex;
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
 
 /* project use */
 use crate::filter;
 use crate::io;
 use filter::Filter;
 
+//                  begin_a end_a begin_b end_b
+type Coordinates = (u64, u64, u64, u64);
 //               read_a  strand read_a strand length
-type LineType = (String, char, String, char, u64);
+type LineType = (String, char, String, char, u64, Coordinates);
 //                      read_a strand leng_a read_b strand len_b position len_containment
-type ContainmentType = (String, char, u64, String, char, u64, u64, u64);
+type ContainmentType = (String, char, u64, String, char, u64, u64, u64, Coordinates);
 
-type Graph = petgraph::Graph<(String, u64), LineType>;
+type Graph = petgraph::stable_graph::StableGraph<(String, u64), LineType>;
+
+/// Read in one orientation
+type Vertex = (NodeIndex, char);
+
+/// Inner reads and edges of a non-branching path
+type Branch = (Vec<NodeIndex>, Vec<EdgeIndex>);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mark {
+    InPlay,
+    Eliminated,
+}
+
+fn reverse(strand: char) -> char {
+    if strand == '+' {
+        '-'
+    } else {
+        '+'
+    }
+}
+
+fn a_to_b(record: &dyn io::MappingRecord) -> Coordinates {
+    (
+        record.begin_a(),
+        record.end_a(),
+        record.begin_b(),
+        record.end_b(),
+    )
+}
+
+fn b_to_a(record: &dyn io::MappingRecord) -> Coordinates {
+    (
+        record.begin_b(),
+        record.end_b(),
+        record.begin_a(),
+        record.end_a(),
+    )
+}
+
+/// Position in gfa2 format, `$` mark end of segment
+fn gfa2_position(position: u64, length: u64) -> String {
+    if position == length {
+        format!("{}$", position)
+    } else {
+        position.to_string()
+    }
+}
 
 pub struct Gfa1 {
     keep_internal: bool,
@@ -50,14 +100,19 @@ pub struct Gfa1 {
 }
 
 impl Gfa1 {
-    pub fn new(keep_internal: bool, keep_containment: bool, internal_threshold: f64) -> Self {
+    pub fn new(
+        keep_internal: bool,
+        keep_containment: bool,
+        internal_threshold: f64,
+        max_overhang: u64,
+    ) -> Self {
         Gfa1 {
             keep_internal,
             keep_containment,
             graph: Graph::new(),
             containments: HashMap::new(),
-            test_containment: filter::Containment::new(internal_threshold),
-            test_internalmatch: filter::InternalMatch::new(internal_threshold),
+            test_containment: filter::Containment::new(internal_threshold, max_overhang),
+            test_internalmatch: filter::InternalMatch::new(internal_threshold, max_overhang),
             node2index: HashMap::new(),
         }
     }
@@ -88,6 +143,7 @@ impl Gfa1 {
                         record.length_a(),
                         record.begin_b(),
                         record.length(),
+                        b_to_a(record),
                     ),
                 );
             } else if record.begin_a() >= record.begin_b()
@@ -105,6 +161,7 @@ impl Gfa1 {
                         record.length_b(),
                         record.begin_a(),
                         record.length(),
+                        a_to_b(record),
                     ),
                 );
             } else {
@@ -129,6 +186,7 @@ impl Gfa1 {
                     record.length_a(),
                     record.begin_b(),
                     record.length(),
+                    b_to_a(record),
                 ),
             );
         } else if record.begin_a() >= record.len_to_end_b()
@@ -146,6 +204,7 @@ impl Gfa1 {
                     record.length_b(),
                     record.begin_a(),
                     record.length(),
+                    a_to_b(record),
                 ),
             );
         } else {
@@ -167,56 +226,494 @@ impl Gfa1 {
         let node_a = self.add_node((record.read_a(), record.length_a()));
         let node_b = self.add_node((record.read_b(), record.length_b()));
 
-        if record.strand() == '+' {
-            if record.begin_a() > record.begin_b() {
+        match io::gfa::OverlapEnd::dovetail(record) {
+            io::gfa::OverlapEnd::SuffixPrefix => {
                 // A overlap B
                 self.add_edge(
                     node_a,
                     node_b,
-                    (record.read_a(), '+', record.read_b(), '+', record.length()),
+                    (
+                        record.read_a(),
+                        '+',
+                        record.read_b(),
+                        '+',
+                        record.length(),
+                        a_to_b(record),
+                    ),
                 );
-            } else {
+            }
+            io::gfa::OverlapEnd::PrefixSuffix => {
                 // B overlap A
                 self.add_edge(
                     node_b,
                     node_a,
-                    (record.read_b(), '+', record.read_a(), '+', record.length()),
+                    (
+                        record.read_b(),
+                        '+',
+                        record.read_a(),
+                        '+',
+                        record.length(),
+                        b_to_a(record),
+                    ),
                 );
             }
-        } else if record.begin_a() > record.len_to_end_a() {
-            if record.begin_a() > record.len_to_end_b() {
-                // A overlap B
-                self.add_edge(
-                    node_a,
-                    node_b,
-                    (record.read_a(), '+', record.read_b(), '-', record.length()),
-                );
-            } else {
-                // B overlap Af
-                self.add_edge(
-                    node_b,
-                    node_a,
-                    (record.read_b(), '+', record.read_a(), '-', record.length()),
-                );
+            io::gfa::OverlapEnd::SuffixSuffix => {
+                if record.begin_a() > record.len_to_end_b() {
+                    // A overlap B
+                    self.add_edge(
+                        node_a,
+                        node_b,
+                        (
+                            record.read_a(),
+                            '+',
+                            record.read_b(),
+                            '-',
+                            record.length(),
+                            a_to_b(record),
+                        ),
+                    );
+                } else {
+                    // B overlap A
+                    self.add_edge(
+                        node_b,
+                        node_a,
+                        (
+                            record.read_b(),
+                            '+',
+                            record.read_a(),
+                            '-',
+                            record.length(),
+                            b_to_a(record),
+                        ),
+                    );
+                }
+            }
+            io::gfa::OverlapEnd::PrefixPrefix => {
+                if (record.length_a() - record.begin_a()) > record.end_b() {
+                    // A overlap B
+                    self.add_edge(
+                        node_a,
+                        node_b,
+                        (
+                            record.read_a(),
+                            '-',
+                            record.read_b(),
+                            '+',
+                            record.length(),
+                            a_to_b(record),
+                        ),
+                    );
+                } else {
+                    // B overlap A
+                    self.add_edge(
+                        node_b,
+                        node_a,
+                        (
+                            record.read_b(),
+                            '-',
+                            record.read_a(),
+                            '+',
+                            record.length(),
+                            b_to_a(record),
+                        ),
+                    );
+                }
             }
-        } else if (record.length_a() - record.begin_a()) > record.end_b() {
-            // A overlap B
-            self.add_edge(
-                node_a,
-                node_b,
-                (record.read_a(), '-', record.read_b(), '+', record.length()),
-            );
-        } else {
-            // B overlap A
-            self.add_edge(
-                node_b,
-                node_a,
-                (record.read_b(), '-', record.read_a(), '+', record.length()),
-            );
         }
     }
 
-    pub fn write<W: std::io::Write>(&mut self, writer: &mut W) {
+    /// Myers transitive reduction of the bidirected graph, contained reads are removed before, return number of removed edges.
+    ///
+    /// Each link is used in its two orientations, an arc length is the offset of second read on first read, the part of first read before overlap minus the part of second read before overlap. Edge v→x is removed if a path v→w→x have a length lower than longest arc of v plus fuzz.
+    pub fn transitive_reduction(&mut self, fuzz: u64) -> usize {
+        self.remove_contained();
+
+        let arcs = self.arcs();
+
+        let mut mark: HashMap<Vertex, Mark> = HashMap::new();
+        let mut reduce = HashSet::new();
+        let empty = Vec::new();
+
+        for (v, out_v) in arcs.iter() {
+            for (w, _, _) in out_v {
+                mark.insert(*w, Mark::InPlay);
+            }
+
+            let longest = out_v.iter().map(|(_, len, _)| *len).max().unwrap_or(0) + fuzz;
+
+            for (w, len_vw, _) in out_v {
+                if mark.get(w) != Some(&Mark::InPlay) {
+                    continue;
+                }
+
+                for (x, len_wx, _) in arcs.get(w).unwrap_or(&empty) {
+                    if len_vw + len_wx > longest {
+                        break;
+                    }
+
+                    if x != v && mark.get(x) == Some(&Mark::InPlay) {
+                        mark.insert(*x, Mark::Eliminated);
+                    }
+                }
+            }
+
+            for (w, _, _) in out_v {
+                for (i, (x, len_wx, _)) in arcs.get(w).unwrap_or(&empty).iter().enumerate() {
+                    if i != 0 && *len_wx >= fuzz {
+                        break;
+                    }
+
+                    if x != v && mark.get(x) == Some(&Mark::InPlay) {
+                        mark.insert(*x, Mark::Eliminated);
+                    }
+                }
+            }
+
+            for (w, _, edge) in out_v {
+                if mark.remove(w) == Some(Mark::Eliminated) {
+                    reduce.insert(*edge);
+                }
+            }
+        }
+
+        for edge in reduce.iter() {
+            self.graph.remove_edge(*edge);
+        }
+
+        reduce.len()
+    }
+
+    /// Remove tips, dead-end non-branching paths joining graph on a branching read, with at most max_reads reads and max_length bases, contained reads are removed before, return number of removed reads.
+    ///
+    /// Tips are removed one at a time, a tip is removed only if the read it joins still has another predecessor, until graph doesn't change, a dead-end path not joining graph isn't a tip.
+    pub fn trim_tips(&mut self, max_reads: usize, max_length: u64) -> usize {
+        self.remove_contained();
+
+        let mut removed = 0;
+        loop {
+            let arcs = self.arcs();
+            let empty = Vec::new();
+            let out = |v: &Vertex| arcs.get(v).unwrap_or(&empty);
+            // degree of vertex in current graph, arcs to removed nodes are ignored
+            let degree = |v: &Vertex, remove: &HashSet<NodeIndex>| {
+                out(v)
+                    .iter()
+                    .filter(|(w, _, _)| !remove.contains(&w.0))
+                    .count()
+            };
+            let in_degree =
+                |v: &Vertex, remove: &HashSet<NodeIndex>| degree(&(v.0, reverse(v.1)), remove);
+
+            let mut starts: Vec<&Vertex> = arcs.keys().collect();
+            starts.sort();
+
+            let mut remove: HashSet<NodeIndex> = HashSet::new();
+            for v in starts {
+                if remove.contains(&v.0) || in_degree(v, &remove) != 0 {
+                    continue;
+                }
+
+                let mut tip = vec![v.0];
+                let mut length = 0;
+                let mut current = *v;
+                while degree(&current, &remove) == 1
+                    && tip.len() <= max_reads
+                    && length <= max_length
+                {
+                    let (next, len, _) = *out(&current)
+                        .iter()
+                        .find(|(w, _, _)| !remove.contains(&w.0))
+                        .unwrap();
+                    length += len;
+
+                    // tip is removed only if next keeps another predecessor after previous removals
+                    if in_degree(&next, &remove) > 1 {
+                        if tip.len() <= max_reads && length <= max_length && !tip.contains(&next.0)
+                        {
+                            remove.extend(tip.iter());
+                        }
+                        break;
+                    }
+
+                    if tip.contains(&next.0) {
+                        break;
+                    }
+
+                    tip.push(next.0);
+                    current = next;
+                }
+            }
+
+            if remove.is_empty() {
+                break;
+            }
+
+            removed += remove.len();
+            for node in remove {
+                self.graph.remove_node(node);
+            }
+        }
+
+        removed
+    }
+
+    /// Pop simple bubbles, non-branching paths from one read to another with a length lower than max_length, the path with most reads is kept, contained reads are removed before, return number of popped bubbles.
+    pub fn pop_bubbles(&mut self, max_length: u64) -> usize {
+        self.remove_contained();
+
+        let mut popped = 0;
+        loop {
+            let arcs = self.arcs();
+            let empty = Vec::new();
+            let out = |v: &Vertex| arcs.get(v).unwrap_or(&empty);
+            let in_degree = |v: &Vertex| out(&(v.0, reverse(v.1))).len();
+
+            let mut used: HashSet<NodeIndex> = HashSet::new();
+            let mut remove_nodes: HashSet<NodeIndex> = HashSet::new();
+            let mut remove_edges: HashSet<EdgeIndex> = HashSet::new();
+            let mut nb_bubble = 0;
+
+            let mut sources: Vec<&Vertex> = arcs.keys().filter(|v| out(v).len() > 1).collect();
+            sources.sort();
+
+            for v in sources {
+                if used.contains(&v.0) {
+                    continue;
+                }
+
+                let mut branches: HashMap<Vertex, Vec<Branch>> = HashMap::new();
+                for (w, len, edge) in out(v) {
+                    let mut length = *len;
+                    let mut nodes = Vec::new();
+                    let mut edges = vec![*edge];
+                    let mut current = *w;
+                    while in_degree(&current) == 1
+                        && out(&current).len() == 1
+                        && current.0 != v.0
+                        && length <= max_length
+                    {
+                        let (next, len, edge) = out(&current)[0];
+                        nodes.push(current.0);
+                        edges.push(edge);
+                        length += len;
+                        current = next;
+                    }
+
+                    if length <= max_length && current.0 != v.0 && !nodes.contains(&current.0) {
+                        branches.entry(current).or_default().push((nodes, edges));
+                    }
+                }
+
+                let mut ends: Vec<&Vertex> = branches.keys().collect();
+                ends.sort();
+                for end in ends {
+                    let paths = &branches[end];
+                    if paths.len() < 2
+                        || used.contains(&end.0)
+                        || paths
+                            .iter()
+                            .flat_map(|p| p.0.iter())
+                            .any(|n| used.contains(n))
+                    {
+                        continue;
+                    }
+
+                    let keep = paths
+                        .iter()
+                        .enumerate()
+                        .max_by_key(|(i, p)| (p.0.len(), std::cmp::Reverse(*i)))
+                        .map(|(i, _)| i)
+                        .unwrap();
+
+                    for (i, (nodes, edges)) in paths.iter().enumerate() {
+                        used.extend(nodes.iter());
+                        if i == keep {
+                            continue;
+                        }
+
+                        if nodes.is_empty() {
+                            remove_edges.extend(edges.iter());
+                        } else {
+                            remove_nodes.extend(nodes.iter());
+                        }
+                    }
+
+                    used.insert(v.0);
+                    used.insert(end.0);
+                    nb_bubble += 1;
+                }
+            }
+
+            if nb_bubble == 0 {
+                break;
+            }
+
+            popped += nb_bubble;
+            for edge in remove_edges {
+                self.graph.remove_edge(edge);
+            }
+            for node in remove_nodes {
+                self.graph.remove_node(node);
+            }
+        }
+
+        popped
+    }
+
+    /// Collapse non-branching paths of the bidirected graph in unitigs, contained reads are removed before, except if containments are kept.
+    ///
+    /// A path is extended from v to w if v has one out arc and w one in arc, reads are placed in unitig at the sum of previous arcs length.
+    pub fn unitig_graph(&mut self) -> io::gfa::UnitigGraph {
+        self.remove_contained();
+
+        let arcs = self.arcs();
+        let empty = Vec::new();
+        let out = |v: &Vertex| arcs.get(v).unwrap_or(&empty);
+        let in_degree = |v: &Vertex| out(&(v.0, reverse(v.1))).len();
+
+        let mut visited: HashSet<NodeIndex> = HashSet::new();
+        let mut paths: Vec<(Vec<Vertex>, Vec<u64>, bool)> = Vec::new();
+
+        for node in self.graph.node_indices() {
+            if visited.contains(&node) || self.graph.neighbors_undirected(node).next().is_none() {
+                continue;
+            }
+
+            let mut in_path: HashSet<NodeIndex> = HashSet::new();
+            in_path.insert(node);
+
+            let mut start = (node, '+');
+            while in_degree(&start) == 1 {
+                let (prev, _, _) = out(&(start.0, reverse(start.1)))[0];
+                let prev = (prev.0, reverse(prev.1));
+                if out(&prev).len() != 1 || in_path.contains(&prev.0) || visited.contains(&prev.0) {
+                    break;
+                }
+
+                in_path.insert(prev.0);
+                start = prev;
+            }
+
+            let mut path = vec![start];
+            let mut lengths = Vec::new();
+            let mut circular = false;
+            in_path.clear();
+            in_path.insert(start.0);
+
+            let mut current = start;
+            while out(&current).len() == 1 {
+                let (next, len, _) = out(&current)[0];
+                if next == start {
+                    circular = true;
+                    lengths.push(len);
+                    break;
+                }
+
+                if in_degree(&next) != 1 || in_path.contains(&next.0) || visited.contains(&next.0) {
+                    break;
+                }
+
+                in_path.insert(next.0);
+                lengths.push(len);
+                path.push(next);
+                current = next;
+            }
+
+            visited.extend(in_path);
+            paths.push((path, lengths, circular));
+        }
+
+        let mut begins: HashMap<Vertex, (usize, char)> = HashMap::new();
+        let mut unitigs = Vec::new();
+        for (index, (path, lengths, circular)) in paths.iter().enumerate() {
+            let mut reads = Vec::new();
+            let mut offset = 0;
+            for (i, (node, strand)) in path.iter().enumerate() {
+                let (name, len) = self.graph.node_weight(*node).unwrap();
+                let used = lengths.get(i).copied().unwrap_or(*len);
+                reads.push((name.clone(), *strand, offset, used));
+                offset += used;
+            }
+
+            unitigs.push(io::gfa::Unitig {
+                name: format!("utg{:06}{}", index + 1, if *circular { 'c' } else { 'l' }),
+                reads,
+            });
+
+            if !circular {
+                let last = path.last().unwrap();
+                begins.insert(path[0], (index, '+'));
+                begins.insert((last.0, reverse(last.1)), (index, '-'));
+            }
+        }
+
+        let mut links = Vec::new();
+        let mut seen = HashSet::new();
+        for (index, (path, _, circular)) in paths.iter().enumerate() {
+            if *circular {
+                continue;
+            }
+
+            let last = path.last().unwrap();
+            for (end, strand) in [(*last, '+'), ((path[0].0, reverse(path[0].1)), '-')] {
+                for (next, _, edge) in out(&end) {
+                    if let Some((other, other_strand)) = begins.get(next) {
+                        let link = (index, strand, *other, *other_strand);
+                        let rev_link = (*other, reverse(*other_strand), index, reverse(strand));
+                        if seen.insert(std::cmp::min(link, rev_link)) {
+                            let overlap = self.graph.edge_weight(*edge).unwrap().4;
+                            links.push((link.0, link.1, link.2, link.3, overlap));
+                        }
+                    }
+                }
+            }
+        }
+
+        io::gfa::UnitigGraph { unitigs, links }
+    }
+
+    /// Out arcs of each oriented read sorted by length
+    fn arcs(&self) -> HashMap<Vertex, Vec<(Vertex, u64, EdgeIndex)>> {
+        let mut arcs: HashMap<Vertex, Vec<(Vertex, u64, EdgeIndex)>> = HashMap::new();
+
+        for edge in self.graph.edge_references() {
+            let (_, strand_a, _, strand_b, _, coord) = edge.weight();
+            let len_a = self.graph.node_weight(edge.source()).unwrap().1;
+            let len_b = self.graph.node_weight(edge.target()).unwrap().1;
+
+            // begin of overlap on each read in the orientation of the arc
+            let begin = |strand: char, begin: u64, end: u64, len: u64| {
+                if strand == '+' {
+                    begin
+                } else {
+                    len.saturating_sub(end)
+                }
+            };
+
+            // part of first read before overlap minus overhang of second read before overlap, overhang after overlap isn't counted
+            let len_from_a = begin(*strand_a, coord.0, coord.1, len_a)
+                .saturating_sub(begin(*strand_b, coord.2, coord.3, len_b));
+            let len_from_b = begin(reverse(*strand_b), coord.2, coord.3, len_b)
+                .saturating_sub(begin(reverse(*strand_a), coord.0, coord.1, len_a));
+
+            arcs.entry((edge.source(), *strand_a)).or_default().push((
+                (edge.target(), *strand_b),
+                len_from_a,
+                edge.id(),
+            ));
+            arcs.entry((edge.target(), reverse(*strand_b)))
+                .or_default()
+                .push(((edge.source(), reverse(*strand_a)), len_from_b, edge.id()));
+        }
+
+        for out in arcs.values_mut() {
+            out.sort_by_key(|(_, len, _)| *len);
+        }
+
+        arcs
+    }
+
+    fn remove_contained(&mut self) {
         if !self.keep_containment {
             let remove_key: Vec<((String, u64), ContainmentType)> =
                 self.containments.drain().collect();
@@ -225,25 +722,21 @@ impl Gfa1 {
                 self.graph.remove_node(index);
             }
         }
+    }
 
-        writer
-            .write_all(b"H\tVN:Z:1.0\n")
-            .expect("Error durring gfa1 write");
+    /// Segments used by containments or edges
+    fn segments(&self) -> Vec<(&String, &u64)> {
+        let mut segments = Vec::new();
 
         let mut writed = HashSet::new();
-        for (read_a, _, len_a, read_b, _, len_b, _, _) in self.containments.values() {
+        for (read_a, _, len_a, read_b, _, len_b, _, _, _) in self.containments.values() {
             if !writed.contains(&(read_a, len_a)) {
-                writer
-                    .write_fmt(format_args!("S\t{}\t*\tLN:i:{}\n", read_a, len_a))
-                    .expect("Error durring gfa1 write");
-
+                segments.push((read_a, len_a));
                 writed.insert((read_a, len_a));
             }
 
             if !writed.contains(&(read_b, len_b)) {
-                writer
-                    .write_fmt(format_args!("S\t{}\t*\tLN:i:{}\n", read_b, len_b))
-                    .expect("Error durring gfa1 write");
+                segments.push((read_b, len_b));
                 writed.insert((read_b, len_b));
             }
         }
@@ -255,11 +748,70 @@ impl Gfa1 {
                     continue;
                 }
 
-                writer
-                    .write_fmt(format_args!("S\t{}\t*\tLN:i:{}\n", segment.0, segment.1))
-                    .expect("Error durring gfa1 write");
+                segments.push((&segment.0, &segment.1));
+            }
+        }
+
+        segments
+    }
+
+    /// Write segments, if a fasta or fastq file is given sequences are read from it.
+    ///
+    /// Reads file is streamed, only names of segments are stored, segments are written in order of reads file and segments without sequence at end.
+    fn write_segments<W: std::io::Write>(&self, writer: &mut W, reads: Option<&str>, gfa2: bool) {
+        let segments = self.segments();
+
+        let mut remaining: HashMap<&str, u64> = segments
+            .iter()
+            .map(|(name, length)| (name.as_str(), **length))
+            .collect();
+
+        if let Some(path) = reads {
+            let (input, _) = niffler::from_path(path).expect("Can't open reads file");
+
+            io::fastx::for_each(std::io::BufReader::new(input), |name, sequence| {
+                if let Some(length) = remaining.remove(name) {
+                    Gfa1::write_segment(writer, name, length, sequence, gfa2);
+                }
+            })
+            .expect("Error durring reads file read");
+        }
+
+        for (name, length) in segments {
+            if remaining.contains_key(name.as_str()) {
+                Gfa1::write_segment(writer, name, *length, b"*", gfa2);
             }
         }
+    }
+
+    fn write_segment<W: std::io::Write>(
+        writer: &mut W,
+        name: &str,
+        length: u64,
+        sequence: &[u8],
+        gfa2: bool,
+    ) {
+        let sequence = std::str::from_utf8(sequence).expect("Sequence isn't valid utf8");
+
+        if gfa2 {
+            writer
+                .write_fmt(format_args!("S\t{}\t{}\t{}\n", name, length, sequence))
+                .expect("Error durring gfa2 write");
+        } else {
+            writer
+                .write_fmt(format_args!("S\t{}\t{}\tLN:i:{}\n", name, sequence, length))
+                .expect("Error durring gfa1 write");
+        }
+    }
+
+    pub fn write<W: std::io::Write>(&mut self, writer: &mut W, reads: Option<&str>) {
+        self.remove_contained();
+
+        writer
+            .write_all(b"H\tVN:Z:1.0\n")
+            .expect("Error durring gfa1 write");
+
+        self.write_segments(writer, reads, false);
 
         for edge in self.graph.edge_references() {
             writer
@@ -284,6 +836,56 @@ impl Gfa1 {
         }
     }
 
+    /// Write graph in gfa2 format, dovetails, containments and internal matches are written as edge with coordinates on both reads
+    pub fn write_gfa2<W: std::io::Write>(&mut self, writer: &mut W, reads: Option<&str>) {
+        self.remove_contained();
+
+        writer
+            .write_all(b"H\tVN:Z:2.0\n")
+            .expect("Error durring gfa2 write");
+
+        self.write_segments(writer, reads, true);
+
+        for edge in self.graph.edge_references() {
+            let (read_a, strand_a, read_b, strand_b, _, coord) = edge.weight();
+            let len_a = self.graph.node_weight(edge.source()).unwrap().1;
+            let len_b = self.graph.node_weight(edge.target()).unwrap().1;
+
+            Gfa1::write_gfa2_edge(
+                writer,
+                (read_a, len_a),
+                (read_b, len_b),
+                if strand_a == strand_b { '+' } else { '-' },
+                coord,
+            );
+        }
+
+        for (read_a, _, len_a, read_b, strand, len_b, _, _, coord) in self.containments.values() {
+            Gfa1::write_gfa2_edge(writer, (read_a, *len_a), (read_b, *len_b), *strand, coord);
+        }
+    }
+
+    fn write_gfa2_edge<W: std::io::Write>(
+        writer: &mut W,
+        segment_a: (&String, u64),
+        segment_b: (&String, u64),
+        strand: char,
+        coord: &Coordinates,
+    ) {
+        writer
+            .write_fmt(format_args!(
+                "E\t*\t{}+\t{}{}\t{}\t{}\t{}\t{}\t*\n",
+                segment_a.0,
+                segment_b.0,
+                strand,
+                gfa2_position(coord.0, segment_a.1),
+                gfa2_position(coord.1, segment_a.1),
+                gfa2_position(coord.2, segment_b.1),
+                gfa2_position(coord.3, segment_b.1),
+            ))
+            .expect("Error durring gfa2 write");
+    }
+
     fn add_node(&mut self, node: (String, u64)) -> petgraph::graph::NodeIndex {
         let graph = &mut self.graph;
         *self
@@ -302,3 +904,267 @@ impl Gfa1 {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    fn record(
+        read_a: &str,
+        begin_a: u64,
+        end_a: u64,
+        strand: char,
+        read_b: &str,
+        begin_b: u64,
+        end_b: u64,
+    ) -> io::paf::Record {
+        io::paf::Record {
+            read_a: read_a.to_string(),
+            length_a: 20000,
+            begin_a,
+            end_a,
+            strand,
+            read_b: read_b.to_string(),
+            length_b: 20000,
+            begin_b,
+            end_b,
+            nb_match_base: end_a - begin_a,
+            nb_base: end_a - begin_a,
+            mapping_quality: 255,
+            sam_field: Vec::new(),
+            position: (0, 50),
+        }
+    }
+
+    #[test]
+    fn gfa2_dovetail() {
+        let mut gfa = Gfa1::new(false, false, 0.8, 1000);
+        gfa.add(&record("read_2", 0, 5000, '+', "read_1", 15000, 20000));
+
+        let mut output = Vec::new();
+        gfa.write_gfa2(&mut output, None);
+
+        assert_eq!(
+            std::str::from_utf8(&output).unwrap(),
+            "H\tVN:Z:2.0\nS\tread_2\t20000\t*\nS\tread_1\t20000\t*\nE\t*\tread_1+\tread_2+\t15000\t20000$\t0\t5000\t*\n"
+        );
+    }
+
+    #[test]
+    fn gfa2_containment() {
+        let mut gfa = Gfa1::new(false, true, 0.8, 1000);
+        gfa.add(&io::paf::Record {
+            length_b: 5000,
+            ..record("read_1", 5000, 10000, '-', "read_2", 0, 5000)
+        });
+
+        let mut output = Vec::new();
+        gfa.write_gfa2(&mut output, None);
+
+        assert_eq!(
+            std::str::from_utf8(&output).unwrap(),
+            "H\tVN:Z:2.0\nS\tread_1\t20000\t*\nS\tread_2\t5000\t*\nE\t*\tread_1+\tread_2-\t5000\t10000\t0\t5000$\t*\n"
+        );
+    }
+
+    fn layout(reverse_c: bool) -> Vec<io::paf::Record> {
+        let mut records = vec![
+            record("read_a", 3000, 10000, '+', "read_b", 0, 7000),
+            record("read_b", 3000, 10000, '+', "read_c", 0, 7000),
+            record("read_a", 6000, 10000, '+', "read_c", 0, 4000),
+        ];
+
+        if reverse_c {
+            records[1] = record("read_b", 3000, 10000, '-', "read_c", 3000, 10000);
+            records[2] = record("read_a", 6000, 10000, '-', "read_c", 6000, 10000);
+        }
+
+        records
+            .into_iter()
+            .map(|r| io::paf::Record {
+                length_a: 10000,
+                length_b: 10000,
+                ..r
+            })
+            .collect()
+    }
+
+    #[test]
+    fn transitive_reduction() {
+        for reverse_c in [false, true] {
+            let mut gfa = Gfa1::new(false, false, 0.8, 1000);
+            for r in layout(reverse_c) {
+                gfa.add(&r);
+            }
+
+            assert_eq!(gfa.transitive_reduction(100), 1);
+            assert_eq!(gfa.graph.edge_count(), 2);
+
+            let links: HashSet<(String, String)> = gfa
+                .graph
+                .edge_weights()
+                .map(|e| (e.0.clone(), e.2.clone()))
+                .collect();
+            assert!(links.contains(&("read_a".to_string(), "read_b".to_string())));
+            assert!(links.contains(&("read_b".to_string(), "read_c".to_string())));
+        }
+    }
+
+    #[test]
+    fn transitive_reduction_no_path() {
+        let mut gfa = Gfa1::new(false, false, 0.8, 1000);
+        let records = layout(false);
+        gfa.add(&records[0]);
+        gfa.add(&records[2]);
+
+        assert_eq!(gfa.transitive_reduction(100), 0);
+        assert_eq!(gfa.graph.edge_count(), 2);
+    }
+
+    #[test]
+    fn unitig_graph() {
+        for reverse_c in [false, true] {
+            let mut gfa = Gfa1::new(false, false, 0.8, 1000);
+            for r in layout(reverse_c) {
+                gfa.add(&r);
+            }
+            gfa.transitive_reduction(100);
+
+            let graph = gfa.unitig_graph();
+            let strand_c = if reverse_c { '-' } else { '+' };
+
+            assert_eq!(graph.links, Vec::new());
+            assert_eq!(
+                graph.unitigs,
+                vec![io::gfa::Unitig {
+                    name: "utg000001l".to_string(),
+                    reads: vec![
+                        ("read_a".to_string(), '+', 0, 3000),
+                        ("read_b".to_string(), '+', 3000, 3000),
+                        ("read_c".to_string(), strand_c, 6000, 10000),
+                    ],
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn unitig_graph_branch() {
+        let mut gfa = Gfa1::new(false, false, 0.8, 1000);
+        for r in layout(false).into_iter().take(2) {
+            gfa.add(&r);
+        }
+        gfa.add(&io::paf::Record {
+            length_a: 10000,
+            length_b: 10000,
+            ..record("read_a", 2000, 10000, '+', "read_d", 0, 8000)
+        });
+
+        let graph = gfa.unitig_graph();
+        let names: Vec<&String> = graph.unitigs.iter().map(|u| &u.reads[0].0).collect();
+
+        assert_eq!(names, vec!["read_a", "read_b", "read_d"]);
+        assert_eq!(
+            graph.links,
+            vec![(0, '+', 2, '+', 8000), (0, '+', 1, '+', 7000)]
+        );
+    }
+
+    fn short_reads(records: Vec<io::paf::Record>) -> Gfa1 {
+        let mut gfa = Gfa1::new(false, false, 0.8, 1000);
+        for r in records {
+            gfa.add(&io::paf::Record {
+                length_a: 10000,
+                length_b: 10000,
+                ..r
+            });
+        }
+
+        gfa
+    }
+
+    fn reads_in_graph(gfa: &Gfa1) -> Vec<String> {
+        let mut reads: Vec<String> = gfa.segments().iter().map(|s| s.0.clone()).collect();
+        reads.sort();
+
+        reads
+    }
+
+    #[test]
+    fn trim_tips() {
+        let mut records = layout(false);
+        records.remove(2);
+        records.push(record("read_t", 5000, 10000, '+', "read_c", 0, 5000));
+
+        let mut gfa = short_reads(records.clone());
+        assert_eq!(gfa.trim_tips(1, 1000), 0);
+        assert_eq!(gfa.trim_tips(1, 50000), 1);
+        assert_eq!(reads_in_graph(&gfa), vec!["read_a", "read_b", "read_c"]);
+
+        records.pop();
+        let mut gfa = short_reads(records);
+        assert_eq!(gfa.trim_tips(4, 50000), 0);
+    }
+
+    #[test]
+    fn trim_tips_fork() {
+        // read_a and read_t are both tips of read_c, only one can be removed
+        let records = vec![
+            record("read_a", 3000, 10000, '+', "read_c", 0, 7000),
+            record("read_t", 3000, 10000, '+', "read_c", 0, 7000),
+            record("read_c", 3000, 10000, '+', "read_d", 0, 7000),
+        ];
+
+        let mut gfa = short_reads(records);
+        assert_eq!(gfa.trim_tips(1, 50000), 1);
+        assert_eq!(reads_in_graph(&gfa), vec!["read_c", "read_d", "read_t"]);
+    }
+
+    #[test]
+    fn pop_bubbles() {
+        let records = vec![
+            record("read_a", 3000, 10000, '+', "read_b1", 0, 7000),
+            record("read_b1", 3000, 10000, '+', "read_b2", 0, 7000),
+            record("read_b2", 3000, 10000, '+', "read_d", 0, 7000),
+            record("read_a", 3000, 10000, '+', "read_c", 0, 7000),
+            record("read_c", 6000, 10000, '+', "read_d", 0, 4000),
+        ];
+
+        let mut gfa = short_reads(records.clone());
+        assert_eq!(gfa.pop_bubbles(5000), 0);
+
+        let mut gfa = short_reads(records);
+        assert_eq!(gfa.pop_bubbles(50000), 1);
+        assert_eq!(
+            reads_in_graph(&gfa),
+            vec!["read_a", "read_b1", "read_b2", "read_d"]
+        );
+    }
+
+    #[test]
+    fn arc_length_overhang() {
+        for reverse_c in [false, true] {
+            // read_a have a 100 bases tail overhang, read_b a 50 bases head overhang
+            let mut records = vec![
+                record("read_a", 3000, 9900, '+', "read_b", 50, 7000),
+                record("read_b", 3000, 10000, '+', "read_c", 0, 7000),
+            ];
+            if reverse_c {
+                records[1] = record("read_b", 3000, 10000, '-', "read_c", 3000, 10000);
+            }
+
+            let graph = short_reads(records).unitig_graph();
+            let strand_c = if reverse_c { '-' } else { '+' };
+
+            assert_eq!(
+                graph.unitigs[0].reads,
+                vec![
+                    ("read_a".to_string(), '+', 0, 2950),
+                    ("read_b".to_string(), '+', 2950, 3000),
+                    ("read_c".to_string(), strand_c, 5950, 10000),
+                ]
+            );
+        }
+    }
+}
@@ -22,3 +22,9 @@ SOFTWARE.
 
 pub mod gfa1;
 pub use self::gfa1::Gfa1;
+
+pub mod overlap_end;
+pub use self::overlap_end::OverlapEnd;
+
+pub mod unitig;
+pub use self::unitig::{Unitig, UnitigGraph};
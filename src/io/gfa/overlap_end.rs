@@ -0,0 +1,141 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* project use */
+use crate::filter;
+use crate::io;
+use filter::Filter;
+
+/// Read ends involved in an overlap, first end is on read A second on read B, ends are given on forward strand of each read
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OverlapEnd {
+    /// end of A overlap begin of B, reads are on same strand
+    SuffixPrefix,
+    /// begin of A overlap end of B, reads are on same strand
+    PrefixSuffix,
+    /// end of A overlap end of B, reads are on opposite strand
+    SuffixSuffix,
+    /// begin of A overlap begin of B, reads are on opposite strand
+    PrefixPrefix,
+}
+
+impl OverlapEnd {
+    /// Ends of overlap, None if overlap is a containment or an internal match so it extends no read end
+    pub fn classify(
+        record: &dyn io::MappingRecord,
+        internal_threshold: f64,
+        max_overhang: u64,
+    ) -> Option<Self> {
+        if filter::InternalMatch::new(internal_threshold, max_overhang).run(record)
+            || filter::Containment::new(internal_threshold, max_overhang).run(record)
+        {
+            None
+        } else {
+            Some(OverlapEnd::dovetail(record))
+        }
+    }
+
+    /// Ends of an overlap already known to be a dovetail
+    pub fn dovetail(record: &dyn io::MappingRecord) -> Self {
+        if record.strand() == '+' {
+            if record.begin_a() > record.begin_b() {
+                OverlapEnd::SuffixPrefix
+            } else {
+                OverlapEnd::PrefixSuffix
+            }
+        } else if record.begin_a() > record.len_to_end_a() {
+            OverlapEnd::SuffixSuffix
+        } else {
+            OverlapEnd::PrefixPrefix
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    fn record(begin_a: u64, end_a: u64, strand: char, begin_b: u64, end_b: u64) -> io::paf::Record {
+        io::paf::Record {
+            read_a: "read_1".to_string(),
+            length_a: 20000,
+            begin_a,
+            end_a,
+            strand,
+            read_b: "read_2".to_string(),
+            length_b: 20000,
+            begin_b,
+            end_b,
+            nb_match_base: 5000,
+            nb_base: 5000,
+            mapping_quality: 255,
+            sam_field: Vec::new(),
+            position: (0, 50),
+        }
+    }
+
+    #[test]
+    fn dovetail() {
+        assert_eq!(
+            OverlapEnd::dovetail(&record(15000, 20000, '+', 0, 5000)),
+            OverlapEnd::SuffixPrefix
+        );
+        assert_eq!(
+            OverlapEnd::dovetail(&record(0, 5000, '+', 15000, 20000)),
+            OverlapEnd::PrefixSuffix
+        );
+        assert_eq!(
+            OverlapEnd::dovetail(&record(15000, 20000, '-', 15000, 20000)),
+            OverlapEnd::SuffixSuffix
+        );
+        assert_eq!(
+            OverlapEnd::dovetail(&record(0, 5000, '-', 0, 5000)),
+            OverlapEnd::PrefixPrefix
+        );
+    }
+
+    #[test]
+    fn classify() {
+        assert_eq!(
+            OverlapEnd::classify(&record(15000, 20000, '+', 0, 5000), 0.8, 1000),
+            Some(OverlapEnd::SuffixPrefix)
+        );
+        // read_2 is contained in read_1
+        assert_eq!(
+            OverlapEnd::classify(
+                &io::paf::Record {
+                    length_b: 5000,
+                    ..record(10000, 15000, '+', 0, 5000)
+                },
+                0.8,
+                1000
+            ),
+            None
+        );
+        // internal match
+        assert_eq!(
+            OverlapEnd::classify(&record(10000, 15000, '+', 2000, 7000), 0.8, 1000),
+            None
+        );
+    }
+}
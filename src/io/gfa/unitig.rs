@@ -0,0 +1,260 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* std use */
+use std::collections::HashMap;
+
+/* project use */
+use crate::io;
+
+/// A non-branching path of reads
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unitig {
+    pub name: String,
+    /// read name, strand, offset in unitig, length of read used in unitig
+    pub reads: Vec<(String, char, u64, u64)>,
+}
+
+impl Unitig {
+    pub fn length(&self) -> u64 {
+        self.reads
+            .last()
+            .map_or(0, |(_, _, offset, used)| offset + used)
+    }
+
+    /// Concatenate part of reads used in unitig, None if a read sequence is missing
+    ///
+    /// Sequences are part of reads used in unitig already in unitig orientation, as returned by `read_sequences`.
+    pub fn sequence(&self, sequences: &HashMap<String, Vec<u8>>) -> Option<Vec<u8>> {
+        let mut sequence = Vec::with_capacity(self.length() as usize);
+
+        for (read, _, _, _) in self.reads.iter() {
+            sequence.extend_from_slice(sequences.get(read)?);
+        }
+
+        Some(sequence)
+    }
+}
+
+/// Unitigs and links between them
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitigGraph {
+    pub unitigs: Vec<Unitig>,
+    /// unitig index, strand, unitig index, strand, overlap length
+    pub links: Vec<(usize, char, usize, char, u64)>,
+}
+
+impl UnitigGraph {
+    /// Strand and length of the part of each read used in unitigs
+    pub fn used_parts(&self) -> HashMap<String, (char, u64)> {
+        self.unitigs
+            .iter()
+            .flat_map(|u| {
+                u.reads
+                    .iter()
+                    .map(|(read, strand, _, used)| (read.clone(), (*strand, *used)))
+            })
+            .collect()
+    }
+
+    /// Write unitigs in gfa1, `a` lines give position of reads in unitig
+    pub fn write<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        sequences: Option<&HashMap<String, Vec<u8>>>,
+    ) {
+        writer
+            .write_all(b"H\tVN:Z:1.0\n")
+            .expect("Error durring gfa1 write");
+
+        for unitig in self.unitigs.iter() {
+            let sequence = sequences.and_then(|s| unitig.sequence(s)).map_or_else(
+                || "*".to_string(),
+                |s| String::from_utf8_lossy(&s).to_string(),
+            );
+
+            writer
+                .write_fmt(format_args!(
+                    "S\t{}\t{}\tLN:i:{}\n",
+                    unitig.name,
+                    sequence,
+                    unitig.length()
+                ))
+                .expect("Error durring gfa1 write");
+
+            for (read, strand, offset, used) in unitig.reads.iter() {
+                writer
+                    .write_fmt(format_args!(
+                        "a\t{}\t{}\t{}\t{}\t{}\n",
+                        unitig.name, offset, read, strand, used
+                    ))
+                    .expect("Error durring gfa1 write");
+            }
+        }
+
+        for (a, strand_a, b, strand_b, overlap) in self.links.iter() {
+            writer
+                .write_fmt(format_args!(
+                    "L\t{}\t{}\t{}\t{}\t{}M\n",
+                    self.unitigs[*a].name, strand_a, self.unitigs[*b].name, strand_b, overlap
+                ))
+                .expect("Error durring gfa1 write");
+        }
+    }
+
+    /// Write unitigs sequence in fasta, unitigs with a missing read are skipped
+    pub fn write_fasta<W: std::io::Write>(
+        &self,
+        writer: &mut W,
+        sequences: &HashMap<String, Vec<u8>>,
+    ) {
+        for unitig in self.unitigs.iter() {
+            if let Some(sequence) = unitig.sequence(sequences) {
+                writer
+                    .write_fmt(format_args!(">{} LN:i:{}\n", unitig.name, unitig.length()))
+                    .expect("Error durring fasta write");
+                writer
+                    .write_all(&sequence)
+                    .expect("Error durring fasta write");
+                writer.write_all(b"\n").expect("Error durring fasta write");
+            } else {
+                eprintln!(
+                    "Unitig {} isn't written in fasta, a read sequence is missing",
+                    unitig.name
+                );
+            }
+        }
+    }
+}
+
+/// Load part of reads used in unitigs from a fasta or fastq file (can be compressed)
+///
+/// Reads file is streamed and only the used part of each read is stored, reverse complemented if read is on reverse strand in unitig.
+pub fn read_sequences(
+    path: &str,
+    parts: &HashMap<String, (char, u64)>,
+) -> HashMap<String, Vec<u8>> {
+    let (input, _) = niffler::from_path(path).expect("Can't open reads file");
+
+    let mut sequences = HashMap::new();
+    io::fastx::for_each(std::io::BufReader::new(input), |name, sequence| {
+        if let Some((strand, used)) = parts.get(name) {
+            sequences.insert(name.to_string(), used_part(sequence, *strand, *used));
+        }
+    })
+    .expect("Error durring reads file read");
+
+    sequences
+}
+
+/// First used bases of read in unitig orientation
+fn used_part(sequence: &[u8], strand: char, used: u64) -> Vec<u8> {
+    let used = (used as usize).min(sequence.len());
+
+    if strand == '+' {
+        sequence[..used].to_vec()
+    } else {
+        reverse_complement(&sequence[sequence.len() - used..])
+    }
+}
+
+fn reverse_complement(sequence: &[u8]) -> Vec<u8> {
+    sequence
+        .iter()
+        .rev()
+        .map(|n| match n {
+            b'A' => b'T',
+            b'C' => b'G',
+            b'G' => b'C',
+            b'T' => b'A',
+            b'a' => b't',
+            b'c' => b'g',
+            b'g' => b'c',
+            b't' => b'a',
+            _ => b'N',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    lazy_static! {
+        static ref UNITIG: Unitig = Unitig {
+            name: "utg000001l".to_string(),
+            reads: vec![
+                ("read_1".to_string(), '+', 0, 2),
+                ("read_2".to_string(), '-', 2, 4),
+            ],
+        };
+    }
+
+    #[test]
+    fn revcomp() {
+        assert_eq!(reverse_complement(b"ACGTNacgt"), b"acgtNACGT".to_vec());
+    }
+
+    #[test]
+    fn used() {
+        assert_eq!(used_part(b"AACCG", '+', 2), b"AA".to_vec());
+        assert_eq!(used_part(b"AACCG", '-', 2), b"CG".to_vec());
+        assert_eq!(used_part(b"AACCG", '-', 10), b"CGGTT".to_vec());
+    }
+
+    #[test]
+    fn sequence() {
+        let graph = UnitigGraph {
+            unitigs: vec![UNITIG.clone()],
+            links: Vec::new(),
+        };
+        let parts = graph.used_parts();
+        assert_eq!(parts["read_1"], ('+', 2));
+        assert_eq!(parts["read_2"], ('-', 4));
+
+        let mut sequences = HashMap::new();
+        sequences.insert("read_1".to_string(), used_part(b"AACC", '+', 2));
+
+        assert_eq!(UNITIG.length(), 6);
+        assert_eq!(UNITIG.sequence(&sequences), None);
+
+        sequences.insert("read_2".to_string(), used_part(b"GGTTTT", '-', 4));
+        assert_eq!(UNITIG.sequence(&sequences), Some(b"AAAAAA".to_vec()));
+    }
+
+    #[test]
+    fn write() {
+        let graph = UnitigGraph {
+            unitigs: vec![UNITIG.clone()],
+            links: vec![(0, '+', 0, '-', 2)],
+        };
+
+        let mut output = Vec::new();
+        graph.write(&mut output, None);
+
+        assert_eq!(
+            std::str::from_utf8(&output).unwrap(),
+            "H\tVN:Z:1.0\nS\tutg000001l\t*\tLN:i:6\na\tutg000001l\t0\tread_1\t+\t2\na\tutg000001l\t2\tread_2\t-\t4\nL\tutg000001l\t+\tutg000001l\t-\t2M\n"
+        );
+    }
+}
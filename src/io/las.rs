@@ -0,0 +1,473 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* local use */
+use crate::io;
+
+/* standard use */
+use std::cmp::{max, min};
+use std::io::BufRead;
+
+/// Flag set in las overlap when b read is reverse complemented.
+const COMP_FLAG: u32 = 0x1;
+
+/// Size of an overlap in las file without trace, a Path struct without trace pointer followed by flags, aread, bread and padding.
+///
+/// Fields are little endian 32 bits integers: tlen@0, diffs@4, abpos@8, bbpos@12, aepos@16, bepos@20, flags@24, aread@28 and bread@32.
+const OVERLAP_SIZE: usize = 40;
+
+/// Over this trace spacing trace values are stored on two bytes.
+const TRACE_XOVR: i32 = 125;
+
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub read_a: String,
+    pub length_a: u64,
+    pub begin_a: u64,
+    pub end_a: u64,
+    pub strand: char,
+    pub read_b: String,
+    pub length_b: u64,
+    pub begin_b: u64,
+    pub end_b: u64,
+    pub diffs: u64,
+    pub position: (u64, u64),
+}
+
+impl io::MappingRecord for Record {
+    fn read_a(&self) -> String {
+        self.read_a.clone()
+    }
+
+    fn length_a(&self) -> u64 {
+        self.length_a
+    }
+
+    fn begin_a(&self) -> u64 {
+        self.begin_a
+    }
+
+    fn end_a(&self) -> u64 {
+        self.end_a
+    }
+
+    fn strand(&self) -> char {
+        self.strand
+    }
+
+    fn read_b(&self) -> String {
+        self.read_b.clone()
+    }
+
+    fn length_b(&self) -> u64 {
+        self.length_b
+    }
+
+    fn begin_b(&self) -> u64 {
+        self.begin_b
+    }
+
+    fn end_b(&self) -> u64 {
+        self.end_b
+    }
+
+    fn position(&self) -> (u64, u64) {
+        self.position
+    }
+
+    fn set_position(&mut self, p: (u64, u64)) {
+        self.position = p;
+    }
+
+    fn length(&self) -> u64 {
+        min(self.end_a - self.begin_a, self.end_b - self.begin_b)
+    }
+
+    fn matches(&self) -> u64 {
+        self.block_length().saturating_sub(self.diffs)
+    }
+
+    fn block_length(&self) -> u64 {
+        max(self.end_a - self.begin_a, self.end_b - self.begin_b)
+    }
+
+    fn identity(&self) -> f64 {
+        if self.block_length() == 0 {
+            0.0
+        } else {
+            self.matches() as f64 / self.block_length() as f64
+        }
+    }
+
+    fn mapping_quality(&self) -> Option<u64> {
+        None
+    }
+
+    fn tag(&self, _name: &str) -> Option<io::tag::Value> {
+        None
+    }
+
+    fn len_to_end_a(&self) -> u64 {
+        self.length_a - self.end_a
+    }
+
+    fn len_to_end_b(&self) -> u64 {
+        self.length_b - self.end_b
+    }
+
+    fn set_read_a(&mut self, new_name: String) {
+        self.read_a = new_name;
+    }
+    fn set_read_b(&mut self, new_name: String) {
+        self.read_b = new_name;
+    }
+}
+
+fn invalid_data(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/// Read name and length of reads in database order.
+///
+/// Input can be a `DBdump -rhl` output or a tsv file with read name and read length on each line.
+pub fn read_names<R: std::io::Read>(reader: R) -> std::io::Result<Vec<(String, u64)>> {
+    let mut reads: Vec<(String, u64)> = Vec::new();
+
+    for line in std::io::BufReader::new(reader).lines() {
+        let line = line?;
+        let mut fields = line.split_whitespace();
+
+        match fields.next() {
+            None | Some("+") | Some("@") | Some("R") => (),
+            Some("H") => {
+                let name = fields.nth(1).ok_or_else(|| invalid_data(line.clone()))?;
+                reads.push((name.to_string(), 0));
+            }
+            Some("L") => {
+                let begin = fields.nth(1).and_then(|v| v.parse::<u64>().ok());
+                let end = fields.next().and_then(|v| v.parse::<u64>().ok());
+                match (reads.last_mut(), begin, end) {
+                    (Some(read), Some(b), Some(e)) => read.1 = e - b,
+                    _ => return Err(invalid_data(line.clone())),
+                }
+            }
+            Some(name) => {
+                let length = fields
+                    .next()
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .ok_or_else(|| invalid_data(line.clone()))?;
+                reads.push((name.to_string(), length));
+            }
+        }
+    }
+
+    Ok(reads)
+}
+
+fn le_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        buf[offset],
+        buf[offset + 1],
+        buf[offset + 2],
+        buf[offset + 3],
+    ])
+}
+
+pub struct Records<'a, R: 'a + std::io::Read> {
+    reader: &'a mut Reader<R>,
+}
+
+impl<'a, R: std::io::Read> Records<'a, R> {
+    fn read_header(&mut self) -> std::io::Result<()> {
+        let mut header = [0u8; 12];
+        self.reader.read_exact(&mut header)?;
+
+        let tspace = le_u32(&header, 8) as i32;
+        self.reader.trace_bytes = if tspace <= TRACE_XOVR { 1 } else { 2 };
+
+        Ok(())
+    }
+
+    fn read_name(&self, id: u32) -> std::io::Result<(String, u64)> {
+        self.reader
+            .reads
+            .get(id as usize)
+            .cloned()
+            .ok_or_else(|| invalid_data(format!("Read {} isn't present in read table", id)))
+    }
+
+    /// Read next overlap, return None at end of file.
+    fn read_overlap(&mut self) -> std::io::Result<Option<Record>> {
+        let position = self.reader.position;
+
+        let mut overlap = [0u8; OVERLAP_SIZE];
+        let mut read = 0;
+        while read < OVERLAP_SIZE {
+            let nb = self.reader.inner.read(&mut overlap[read..])?;
+            if nb == 0 {
+                if read == 0 {
+                    return Ok(None);
+                }
+                return Err(std::io::ErrorKind::UnexpectedEof.into());
+            }
+            read += nb;
+        }
+        self.reader.position += OVERLAP_SIZE as u64;
+
+        let trace_len = le_u32(&overlap, 0) as usize;
+        let mut trace = vec![0u8; trace_len * self.reader.trace_bytes];
+        self.reader.read_exact(&mut trace)?;
+
+        let diffs = le_u32(&overlap, 4) as u64;
+        let begin_a = le_u32(&overlap, 8) as u64;
+        let begin_b = le_u32(&overlap, 12) as u64;
+        let end_a = le_u32(&overlap, 16) as u64;
+        let end_b = le_u32(&overlap, 20) as u64;
+        let flags = le_u32(&overlap, 24);
+        let (read_a, length_a) = self.read_name(le_u32(&overlap, 28))?;
+        let (read_b, length_b) = self.read_name(le_u32(&overlap, 32))?;
+
+        if end_a > length_a || end_b > length_b {
+            return Err(invalid_data(format!(
+                "Overlap between {} and {} is outside of reads",
+                read_a, read_b
+            )));
+        }
+
+        // b coordinates of complemented overlap are on reverse strand
+        let (strand, begin_b, end_b) = if flags & COMP_FLAG != 0 {
+            ('-', length_b - end_b, length_b - begin_b)
+        } else {
+            ('+', begin_b, end_b)
+        };
+
+        Ok(Some(Record {
+            read_a,
+            length_a,
+            begin_a,
+            end_a,
+            strand,
+            read_b,
+            length_b,
+            begin_b,
+            end_b,
+            diffs,
+            position: (position, self.reader.position),
+        }))
+    }
+}
+
+impl<'a, R: std::io::Read> Iterator for Records<'a, R> {
+    type Item = csv::Result<Record>;
+
+    fn next(&mut self) -> Option<csv::Result<Record>> {
+        if self.reader.trace_bytes == 0 {
+            if let Err(e) = self.read_header() {
+                return Some(Err(csv::Error::from(e)));
+            }
+        }
+
+        self.read_overlap().map_err(csv::Error::from).transpose()
+    }
+}
+
+/// Reader of daligner las file, reads are identified by their index in reads table.
+pub struct Reader<R: std::io::Read> {
+    inner: R,
+    reads: Vec<(String, u64)>,
+    position: u64,
+    trace_bytes: usize,
+}
+
+impl<R: std::io::Read> Reader<R> {
+    pub fn new(reader: R, reads: Vec<(String, u64)>) -> Self {
+        Reader {
+            inner: reader,
+            reads,
+            position: 0,
+            trace_bytes: 0,
+        }
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> std::io::Result<()> {
+        self.inner.read_exact(buf)?;
+        self.position += buf.len() as u64;
+
+        Ok(())
+    }
+
+    /// Iterate over all records.
+    pub fn records(&mut self) -> Records<'_, R> {
+        Records { reader: self }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    const DBDUMP: &[u8] = b"+ R 3
++ H 24
+@ H 8
+R 1
+H 8 read_1
+L 0 0 12000
+R 2
+H 8 read_2
+L 0 0 10000
+R 3
+H 8 read_3
+L 0 0 10000
+";
+
+    const TSV: &[u8] = b"read_1\t12000
+read_2\t10000
+read_3\t10000
+";
+
+    fn overlap(
+        a: u32,
+        b: u32,
+        flags: u32,
+        diffs: i32,
+        coord: (i32, i32, i32, i32),
+        trace: &[u8],
+    ) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&(trace.len() as i32).to_le_bytes());
+        buf.extend_from_slice(&diffs.to_le_bytes());
+        buf.extend_from_slice(&coord.0.to_le_bytes());
+        buf.extend_from_slice(&coord.1.to_le_bytes());
+        buf.extend_from_slice(&coord.2.to_le_bytes());
+        buf.extend_from_slice(&coord.3.to_le_bytes());
+        buf.extend_from_slice(&flags.to_le_bytes());
+        buf.extend_from_slice(&a.to_le_bytes());
+        buf.extend_from_slice(&b.to_le_bytes());
+        buf.extend_from_slice(&[0; 4]);
+        buf.extend_from_slice(trace);
+        buf
+    }
+
+    fn las_file() -> Vec<u8> {
+        let mut file = 2i64.to_le_bytes().to_vec();
+        file.extend_from_slice(&100i32.to_le_bytes());
+        file.extend(overlap(0, 1, 0, 100, (20, 5500, 4500, 10000), &[10, 90]));
+        file.extend(overlap(0, 2, COMP_FLAG, 0, (5500, 5500, 10000, 10000), &[]));
+        file
+    }
+
+    const READ_A: &[&str; 2] = &["read_1", "read_1"];
+    const LENGTH_A: &[u64; 2] = &[12000, 12000];
+    const BEGIN_A: &[u64; 2] = &[20, 5500];
+    const END_A: &[u64; 2] = &[4500, 10000];
+    const STRAND: &[char; 2] = &['+', '-'];
+    const READ_B: &[&str; 2] = &["read_2", "read_3"];
+    const LENGTH_B: &[u64; 2] = &[10000, 10000];
+    const BEGIN_B: &[u64; 2] = &[5500, 0];
+    const END_B: &[u64; 2] = &[10000, 4500];
+    const DIFFS: &[u64; 2] = &[100, 0];
+
+    #[test]
+    fn names() {
+        let dump = read_names(DBDUMP).unwrap();
+        let tsv = read_names(TSV).unwrap();
+
+        assert_eq!(dump, tsv);
+        assert_eq!(
+            dump,
+            vec![
+                ("read_1".to_string(), 12000),
+                ("read_2".to_string(), 10000),
+                ("read_3".to_string(), 10000)
+            ]
+        );
+    }
+
+    #[test]
+    fn read() {
+        let file = las_file();
+        let mut reader = Reader::new(&file[..], read_names(TSV).unwrap());
+
+        let records: Vec<Record> = reader.records().map(|r| r.unwrap()).collect();
+        assert_eq!(records.len(), 2);
+
+        for (i, record) in records.iter().enumerate() {
+            assert_eq!(record.read_a, READ_A[i]);
+            assert_eq!(record.length_a, LENGTH_A[i]);
+            assert_eq!(record.begin_a, BEGIN_A[i]);
+            assert_eq!(record.end_a, END_A[i]);
+            assert_eq!(record.strand, STRAND[i]);
+            assert_eq!(record.read_b, READ_B[i]);
+            assert_eq!(record.length_b, LENGTH_B[i]);
+            assert_eq!(record.begin_b, BEGIN_B[i]);
+            assert_eq!(record.end_b, END_B[i]);
+            assert_eq!(record.diffs, DIFFS[i]);
+        }
+
+        assert_eq!(records[0].position, (12, 54));
+        assert_eq!(records[1].position, (54, file.len() as u64));
+    }
+
+    #[test]
+    fn read_unknown_read() {
+        let file = las_file();
+        let mut reader = Reader::new(&file[..], vec![("read_1".to_string(), 12000)]);
+
+        assert!(reader.records().next().unwrap().is_err());
+    }
+
+    /// Overlap struct of daligner (align.h) on a 64 bits little endian machine, written as in a las file without trace pointer
+    const DALIGNER_OVERLAP: [u8; OVERLAP_SIZE] = [
+        0x02, 0x00, 0x00, 0x00, // path.tlen = 2
+        0x64, 0x00, 0x00, 0x00, // path.diffs = 100
+        0x14, 0x00, 0x00, 0x00, // path.abpos = 20
+        0x7c, 0x15, 0x00, 0x00, // path.bbpos = 5500
+        0x94, 0x11, 0x00, 0x00, // path.aepos = 4500
+        0x10, 0x27, 0x00, 0x00, // path.bepos = 10000
+        0x00, 0x00, 0x00, 0x00, // flags
+        0x00, 0x00, 0x00, 0x00, // aread = 0
+        0x01, 0x00, 0x00, 0x00, // bread = 1
+        0x00, 0x00, 0x00, 0x00, // struct padding
+    ];
+
+    #[test]
+    fn daligner_layout() {
+        let record = overlap(0, 1, 0, 100, (20, 5500, 4500, 10000), &[10, 90]);
+        assert_eq!(&record[..OVERLAP_SIZE], &DALIGNER_OVERLAP[..]);
+
+        let mut file = 1i64.to_le_bytes().to_vec();
+        file.extend_from_slice(&100i32.to_le_bytes());
+        file.extend_from_slice(&DALIGNER_OVERLAP);
+        file.extend_from_slice(&[10, 90]);
+
+        let mut reader = Reader::new(&file[..], read_names(TSV).unwrap());
+        let record = reader.records().next().unwrap().unwrap();
+
+        assert_eq!(
+            (record.begin_a, record.end_a, record.begin_b, record.end_b),
+            (20, 4500, 5500, 10000)
+        );
+        assert_eq!(record.diffs, 100);
+        assert_eq!(record.position, (12, 54));
+    }
+}
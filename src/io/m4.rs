@@ -24,7 +24,7 @@ SOFTWARE.
 use crate::io;
 
 /* standard use */
-use std::cmp::min;
+use std::cmp::{max, min};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Record {
@@ -96,6 +96,30 @@ impl io::MappingRecord for Record {
         min(self.end_a - self.begin_a, self.end_b - self.begin_b)
     }
 
+    fn matches(&self) -> u64 {
+        (self.identity() * self.block_length() as f64).round() as u64
+    }
+
+    fn block_length(&self) -> u64 {
+        max(self.end_a - self.begin_a, self.end_b - self.begin_b)
+    }
+
+    fn identity(&self) -> f64 {
+        1.0 - self.error
+    }
+
+    fn mapping_quality(&self) -> Option<u64> {
+        None
+    }
+
+    fn tag(&self, name: &str) -> Option<io::tag::Value> {
+        if name == "cm" {
+            Some(io::tag::Value::Int(self.shared_min_mers as i64))
+        } else {
+            None
+        }
+    }
+
     fn len_to_end_a(&self) -> u64 {
         self.length_a - self.end_a
     }
@@ -111,6 +135,65 @@ impl io::MappingRecord for Record {
     }
 }
 
+impl From<&Record> for Record {
+    fn from(record: &Record) -> Self {
+        record.clone()
+    }
+}
+
+/// Build a m4 record from a paf record.
+///
+/// - error is 1 - nb_match_base / nb_base
+/// - shared_min_mers is read from cm:i tag, 0 if tag is absent
+/// - strand_a is always 0 and strand_b is 1 if paf strand is '-'
+impl From<&io::paf::Record> for Record {
+    fn from(record: &io::paf::Record) -> Self {
+        let error = if record.nb_base == 0 {
+            0.0
+        } else {
+            1.0 - record.nb_match_base as f64 / record.nb_base as f64
+        };
+
+        let shared_min_mers = io::MappingRecord::tag(record, "cm")
+            .and_then(|value| value.as_f64())
+            .map_or(0, |value| value as u64);
+
+        Record {
+            read_a: record.read_a.clone(),
+            read_b: record.read_b.clone(),
+            error,
+            shared_min_mers,
+            strand_a: '0',
+            begin_a: record.begin_a,
+            end_a: record.end_a,
+            length_a: record.length_a,
+            strand_b: if record.strand == '-' { '1' } else { '0' },
+            begin_b: record.begin_b,
+            end_b: record.end_b,
+            length_b: record.length_b,
+            position: record.position,
+        }
+    }
+}
+
+impl From<&io::sam::Record> for Record {
+    fn from(record: &io::sam::Record) -> Self {
+        Record::from(&io::paf::Record::from(record))
+    }
+}
+
+impl From<&io::las::Record> for Record {
+    fn from(record: &io::las::Record) -> Self {
+        Record::from(&io::paf::Record::from(record))
+    }
+}
+
+impl From<&io::gaf::Record> for Record {
+    fn from(record: &io::gaf::Record) -> Self {
+        Record::from(&io::paf::Record::from(record))
+    }
+}
+
 type RecordInner = (
     String,
     String,
@@ -199,7 +282,7 @@ impl<R: std::io::Read> Reader<R> {
 
 #[derive(Debug)]
 pub struct Writer<W: std::io::Write> {
-    inner: csv::Writer<W>,
+    pub(super) inner: csv::Writer<W>,
 }
 
 impl<W: std::io::Write> Writer<W> {
@@ -316,4 +399,40 @@ mod test {
 
         assert_eq!(writer.inner.into_inner().unwrap(), M4_FILE);
     }
+
+    #[test]
+    fn from_paf() {
+        let mut reader = io::paf::Reader::new(
+            &b"1\t1000\t100\t450\t-\t2\t1000\t550\t900\t315\t350\t60\tcm:i:2\n"[..],
+        );
+        let paf = reader.records().next().unwrap().unwrap();
+
+        let record = Record::from(&paf);
+
+        assert_eq!(record.read_a, "1");
+        assert_eq!(record.read_b, "2");
+        assert!((record.error - 0.1).abs() < 1e-9);
+        assert_eq!(record.shared_min_mers, 2);
+        assert_eq!(record.strand_a, '0');
+        assert_eq!(record.strand_b, '1');
+        assert_eq!(record.begin_a, 100);
+        assert_eq!(record.end_a, 450);
+        assert_eq!(record.length_a, 1000);
+        assert_eq!(record.begin_b, 550);
+        assert_eq!(record.end_b, 900);
+        assert_eq!(record.length_b, 1000);
+    }
+
+    #[test]
+    fn accessors() {
+        let mut reader = Reader::new(M4_FILE);
+        let record = reader.records().next().unwrap().unwrap();
+
+        let record: &dyn io::MappingRecord = &record;
+        assert_eq!(record.block_length(), 350);
+        assert_eq!(record.matches(), 315);
+        assert!((record.identity() - 0.9).abs() < 1e-9);
+        assert_eq!(record.mapping_quality(), None);
+        assert_eq!(record.tag("cm"), Some(io::tag::Value::Int(2)));
+    }
 }
@@ -0,0 +1,193 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* local use */
+use crate::io;
+
+/// Mhap records have same columns as m4 records, read are identified by an integer.
+///
+/// Mhap and m4 layouts are identical, only read id type differ, so mhap reuse m4 record, reader and writer and only check read ids.
+pub type Record = io::m4::Record;
+
+/// Return an error if a read id isn't an integer.
+fn check_ids(record: &Record) -> csv::Result<()> {
+    if record.read_a.parse::<u64>().is_ok() && record.read_b.parse::<u64>().is_ok() {
+        Ok(())
+    } else {
+        Err(csv::Error::from(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "Mhap read id must be integer, {} {}",
+                record.read_a, record.read_b
+            ),
+        )))
+    }
+}
+
+pub struct Records<'a, R: 'a + std::io::Read> {
+    inner: io::m4::Records<'a, R>,
+}
+
+impl<'a, R: std::io::Read> Iterator for Records<'a, R> {
+    type Item = csv::Result<Record>;
+
+    fn next(&mut self) -> Option<csv::Result<Record>> {
+        self.inner
+            .next()
+            .map(|res| res.and_then(|record| check_ids(&record).map(|_| record)))
+    }
+}
+
+/// Reader of mhap file, a m4 reader where read id are checked.
+pub struct Reader<R: std::io::Read> {
+    inner: io::m4::Reader<R>,
+}
+
+impl<R: std::io::Read> Reader<R> {
+    pub fn new(reader: R) -> Self {
+        Reader {
+            inner: io::m4::Reader::new(reader),
+        }
+    }
+
+    /// Iterate over all records.
+    pub fn records(&mut self) -> Records<'_, R> {
+        Records {
+            inner: self.inner.records(),
+        }
+    }
+}
+
+/// Writer of mhap file, a m4 writer where read id are checked.
+pub struct Writer<W: std::io::Write> {
+    inner: io::m4::Writer<W>,
+}
+
+impl<W: std::io::Write> Writer<W> {
+    /// Write to a given writer.
+    pub fn new(writer: W) -> Self {
+        Writer {
+            inner: io::m4::Writer::new(writer),
+        }
+    }
+
+    /// Write a given mhap record, return an error if a read id isn't an integer.
+    pub fn write(&mut self, record: &Record) -> csv::Result<u64> {
+        check_ids(record)?;
+
+        self.inner.write(record)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    const MHAP_FILE: &[u8] = b"1 2 0.1 2 0 100 450 1000 0 550 900 1000
+1 3 0.1 2 0 550 900 1000 1 100 450 1000
+";
+
+    const READ_A: &[&str; 2] = &["1", "1"];
+    const READ_B: &[&str; 2] = &["2", "3"];
+    const ERROR: &[f64; 2] = &[0.1, 0.1];
+    const SHARED_MIN_MERS: &[u64; 2] = &[2, 2];
+    const STRAND_A: &[char; 2] = &['0', '0'];
+    const STRAND_B: &[char; 2] = &['0', '1'];
+    const BEGIN_A: &[u64; 2] = &[100, 550];
+    const END_A: &[u64; 2] = &[450, 900];
+    const LENGTH_A: &[u64; 2] = &[1000, 1000];
+    const BEGIN_B: &[u64; 2] = &[550, 100];
+    const END_B: &[u64; 2] = &[900, 450];
+    const LENGTH_B: &[u64; 2] = &[1000, 1000];
+
+    #[test]
+    fn read() {
+        let mut reader = Reader::new(MHAP_FILE);
+
+        for (i, r) in reader.records().enumerate() {
+            let record = r.unwrap();
+
+            assert_eq!(record.read_a, READ_A[i]);
+            assert_eq!(record.read_b, READ_B[i]);
+            assert_eq!(record.error, ERROR[i]);
+            assert_eq!(record.shared_min_mers, SHARED_MIN_MERS[i]);
+            assert_eq!(record.strand_a, STRAND_A[i]);
+            assert_eq!(record.begin_a, BEGIN_A[i]);
+            assert_eq!(record.end_a, END_A[i]);
+            assert_eq!(record.length_a, LENGTH_A[i]);
+            assert_eq!(record.strand_b, STRAND_B[i]);
+            assert_eq!(record.begin_b, BEGIN_B[i]);
+            assert_eq!(record.end_b, END_B[i]);
+            assert_eq!(record.length_b, LENGTH_B[i]);
+        }
+    }
+
+    #[test]
+    fn read_not_integer_id() {
+        let mut reader = Reader::new(&b"read_1 read_2 0.1 2 0 100 450 1000 0 550 900 1000\n"[..]);
+
+        assert!(reader.records().next().unwrap().is_err());
+    }
+
+    #[test]
+    fn write() {
+        let mut reader = Reader::new(MHAP_FILE);
+        let mut writer = Writer::new(vec![]);
+        for r in reader.records() {
+            writer
+                .write(&r.expect("Error reading record"))
+                .expect("Error writing record");
+        }
+
+        assert_eq!(writer.inner.inner.into_inner().unwrap(), MHAP_FILE);
+    }
+
+    #[test]
+    fn from_paf() {
+        let mut reader = io::paf::Reader::new(
+            &b"1\t1000\t100\t450\t-\t2\t1000\t550\t900\t175\t350\t60\tcm:i:2\n"[..],
+        );
+        let paf = reader.records().next().unwrap().unwrap();
+
+        let mut writer = Writer::new(vec![]);
+        writer.write(&Record::from(&paf)).unwrap();
+
+        assert_eq!(
+            writer.inner.inner.into_inner().unwrap(),
+            b"1 2 0.5 2 0 100 450 1000 1 550 900 1000\n"
+        );
+    }
+
+    #[test]
+    fn from_paf_not_integer_id() {
+        let mut reader = io::paf::Reader::new(
+            &b"read_1\t1000\t100\t450\t-\tread_2\t1000\t550\t900\t175\t350\t60\n"[..],
+        );
+        let paf = reader.records().next().unwrap().unwrap();
+
+        let mut writer = Writer::new(vec![]);
+        assert!(writer.write(&Record::from(&paf)).is_err());
+
+        assert!(writer.inner.inner.into_inner().unwrap().is_empty());
+    }
+}
@@ -20,9 +20,18 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
+pub mod fastx;
+pub mod gaf;
 pub mod gfa;
+pub mod las;
 pub mod m4;
+pub mod mhap;
 pub mod paf;
+pub mod sam;
+pub mod tag;
+
+/* standard use */
+use std::io::{BufRead, Read};
 
 pub trait MappingRecord {
     fn read_a(&self) -> String;
@@ -39,6 +48,17 @@ pub trait MappingRecord {
 
     fn length(&self) -> u64;
 
+    /// Number of matching bases in alignment.
+    fn matches(&self) -> u64;
+    /// Number of bases in alignment, gaps included.
+    fn block_length(&self) -> u64;
+    /// Fraction of matching bases in alignment.
+    fn identity(&self) -> f64;
+    /// Mapping quality, None if format doesn't store it.
+    fn mapping_quality(&self) -> Option<u64>;
+    /// Value of an optional field.
+    fn tag(&self, name: &str) -> Option<tag::Value>;
+
     fn len_to_end_a(&self) -> u64;
     fn len_to_end_b(&self) -> u64;
 
@@ -46,7 +66,237 @@ pub trait MappingRecord {
     fn set_read_b(&mut self, new_name: String);
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MappingFormat {
     Paf,
     M4,
+    Sam,
+    Bam,
+    Las,
+    Mhap,
+    Gaf,
+}
+
+impl From<&str> for MappingFormat {
+    fn from(format: &str) -> Self {
+        match format {
+            "paf" => MappingFormat::Paf,
+            "m4" => MappingFormat::M4,
+            "sam" => MappingFormat::Sam,
+            "bam" => MappingFormat::Bam,
+            "las" => MappingFormat::Las,
+            "mhap" => MappingFormat::Mhap,
+            "gaf" => MappingFormat::Gaf,
+            _ => MappingFormat::Paf,
+        }
+    }
+}
+
+impl MappingFormat {
+    /// Guess format of one mapping line, return None if line match no format.
+    pub fn guess(line: &str) -> Option<Self> {
+        let paf_fields: Vec<&str> = line.split('\t').collect();
+        if paf_fields.len() >= 12
+            && (paf_fields[4] == "+" || paf_fields[4] == "-")
+            && [1, 2, 3, 6, 7, 8, 9, 10, 11]
+                .iter()
+                .all(|i| paf_fields[*i].parse::<u64>().is_ok())
+        {
+            if paf_fields[5].starts_with('>') || paf_fields[5].starts_with('<') {
+                return Some(MappingFormat::Gaf);
+            }
+
+            return Some(MappingFormat::Paf);
+        }
+
+        if line.starts_with('@')
+            || (paf_fields.len() >= 11
+                && paf_fields[1].parse::<u16>().is_ok()
+                && paf_fields[3].parse::<u64>().is_ok()
+                && paf_fields[4].parse::<u8>().is_ok())
+        {
+            return Some(MappingFormat::Sam);
+        }
+
+        let m4_fields: Vec<&str> = line.split(' ').collect();
+        if m4_fields.len() == 12
+            && m4_fields[2].parse::<f64>().is_ok()
+            && (m4_fields[4] == "0" || m4_fields[4] == "1")
+            && (m4_fields[8] == "0" || m4_fields[8] == "1")
+            && [3, 5, 6, 7, 9, 10, 11]
+                .iter()
+                .all(|i| m4_fields[*i].parse::<u64>().is_ok())
+        {
+            // mhap share m4 layout, it's only used if format is forced
+            return Some(MappingFormat::M4);
+        }
+
+        None
+    }
+}
+
+/// Read the first non empty record of input to detect its format.
+///
+/// Bytes consumed during detection are put back in front of the returned reader, so record positions stay valid. An empty input is detected as Paf.
+pub fn detect_format(
+    input: Box<dyn std::io::Read>,
+) -> std::io::Result<(MappingFormat, Box<dyn std::io::Read>)> {
+    let mut reader = std::io::BufReader::new(input);
+    if reader.fill_buf()?.starts_with(b"BAM\x01") {
+        return Ok((MappingFormat::Bam, Box::new(reader)));
+    }
+
+    let mut buffer = Vec::new();
+
+    let format = loop {
+        let line_begin = buffer.len();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break MappingFormat::Paf;
+        }
+
+        let line = String::from_utf8_lossy(&buffer[line_begin..]);
+        let line = line.trim_end_matches(&['\n', '\r'][..]);
+        if line.is_empty() {
+            continue;
+        }
+
+        break MappingFormat::guess(line).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Input format isn't recognized as paf, gaf, m4, mhap, sam or bam, first record is: {}",
+                    line
+                ),
+            )
+        })?;
+    };
+
+    Ok((format, Box::new(std::io::Cursor::new(buffer).chain(reader))))
+}
+
+pub enum Writer<W: std::io::Write> {
+    Paf(paf::Writer<W>),
+    M4(m4::Writer<W>),
+    Mhap(mhap::Writer<W>),
+    Gaf(gaf::Writer<W>),
+}
+
+impl<W: std::io::Write> Writer<W> {
+    /// Sam, bam and las can't be written, mapping are written in paf.
+    pub fn new(writer: W, format: MappingFormat) -> Self {
+        match format {
+            MappingFormat::Paf | MappingFormat::Sam | MappingFormat::Bam | MappingFormat::Las => {
+                Writer::Paf(paf::Writer::new(writer))
+            }
+            MappingFormat::M4 => Writer::M4(m4::Writer::new(writer)),
+            MappingFormat::Mhap => Writer::Mhap(mhap::Writer::new(writer)),
+            MappingFormat::Gaf => Writer::Gaf(gaf::Writer::new(writer)),
+        }
+    }
+
+    /// Convert record in writer format if necessary and write it.
+    pub fn write<R>(&mut self, record: &R) -> csv::Result<u64>
+    where
+        for<'a> paf::Record: From<&'a R>,
+        for<'a> m4::Record: From<&'a R>,
+        for<'a> gaf::Record: From<&'a R>,
+    {
+        match self {
+            Writer::Paf(w) => w.write(&paf::Record::from(record)),
+            Writer::M4(w) => w.write(&m4::Record::from(record)),
+            Writer::Mhap(w) => w.write(&mhap::Record::from(record)),
+            Writer::Gaf(w) => w.write(&gaf::Record::from(record)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    const PAF_FILE: &[u8] = b"1\t12000\t20\t4500\t-\t2\t10000\t5500\t10000\t4500\t4500\t255\ttp:A:P
+";
+
+    const GAF_FILE: &[u8] = b"1\t12000\t20\t4500\t+\t>s1<s2\t10000\t5500\t10000\t4500\t4500\t255
+";
+
+    const M4_FILE: &[u8] = b"
+1 2 0.1 2 0 100 450 1000 0 550 900 1000
+";
+
+    const SAM_FILE: &[u8] = b"@HD\tVN:1.6
+";
+
+    const OTHER_FILE: &[u8] = b"1\t2\t0.1
+";
+
+    #[test]
+    fn detect_paf() {
+        let (format, mut input) = detect_format(Box::new(PAF_FILE)).unwrap();
+
+        assert_eq!(format, MappingFormat::Paf);
+
+        let mut content = Vec::new();
+        input.read_to_end(&mut content).unwrap();
+        assert_eq!(content, PAF_FILE);
+    }
+
+    #[test]
+    fn detect_gaf() {
+        let (format, _) = detect_format(Box::new(GAF_FILE)).unwrap();
+
+        assert_eq!(format, MappingFormat::Gaf);
+    }
+
+    #[test]
+    fn detect_m4() {
+        let (format, mut input) = detect_format(Box::new(M4_FILE)).unwrap();
+
+        assert_eq!(format, MappingFormat::M4);
+
+        let mut content = Vec::new();
+        input.read_to_end(&mut content).unwrap();
+        assert_eq!(content, M4_FILE);
+    }
+
+    #[test]
+    fn detect_m4_string_id() {
+        let (format, _) = detect_format(Box::new(
+            &b"read_1 read_2 0.1 2 0 100 450 1000 0 550 900 1000\n"[..],
+        ))
+        .unwrap();
+
+        assert_eq!(format, MappingFormat::M4);
+    }
+
+    #[test]
+    fn detect_sam() {
+        let (format, _) = detect_format(Box::new(SAM_FILE)).unwrap();
+
+        assert_eq!(format, MappingFormat::Sam);
+    }
+
+    #[test]
+    fn detect_bam() {
+        let (format, mut input) = detect_format(Box::new(&b"BAM\x01\0\0\0\0"[..])).unwrap();
+
+        assert_eq!(format, MappingFormat::Bam);
+
+        let mut content = Vec::new();
+        input.read_to_end(&mut content).unwrap();
+        assert_eq!(content, b"BAM\x01\0\0\0\0");
+    }
+
+    #[test]
+    fn detect_empty() {
+        let (format, _) = detect_format(Box::new(&b""[..])).unwrap();
+
+        assert_eq!(format, MappingFormat::Paf);
+    }
+
+    #[test]
+    fn detect_other() {
+        assert!(detect_format(Box::new(OTHER_FILE)).is_err());
+    }
 }
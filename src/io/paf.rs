@@ -93,6 +93,30 @@ impl io::MappingRecord for Record {
         min(self.end_a - self.begin_a, self.end_b - self.begin_b)
     }
 
+    fn matches(&self) -> u64 {
+        self.nb_match_base
+    }
+
+    fn block_length(&self) -> u64 {
+        self.nb_base
+    }
+
+    fn identity(&self) -> f64 {
+        if self.nb_base == 0 {
+            0.0
+        } else {
+            self.nb_match_base as f64 / self.nb_base as f64
+        }
+    }
+
+    fn mapping_quality(&self) -> Option<u64> {
+        Some(self.mapping_quality)
+    }
+
+    fn tag(&self, name: &str) -> Option<io::tag::Value> {
+        io::tag::find(&self.sam_field, name)
+    }
+
     fn len_to_end_a(&self) -> u64 {
         self.length_a - self.end_a
     }
@@ -109,6 +133,107 @@ impl io::MappingRecord for Record {
     }
 }
 
+impl From<&Record> for Record {
+    fn from(record: &Record) -> Self {
+        record.clone()
+    }
+}
+
+/// Build a paf record from a m4 record.
+///
+/// - strand is the relative strand of m4 record
+/// - nb_base is the longest alignment span and nb_match_base is nb_base * (1 - error)
+/// - mapping_quality is set to 255 (unavailable)
+/// - shared_min_mers is stored in a cm:i tag
+impl From<&io::m4::Record> for Record {
+    fn from(record: &io::m4::Record) -> Self {
+        Record {
+            read_a: record.read_a.clone(),
+            length_a: record.length_a,
+            begin_a: record.begin_a,
+            end_a: record.end_a,
+            strand: io::MappingRecord::strand(record),
+            read_b: record.read_b.clone(),
+            length_b: record.length_b,
+            begin_b: record.begin_b,
+            end_b: record.end_b,
+            nb_match_base: io::MappingRecord::matches(record),
+            nb_base: io::MappingRecord::block_length(record),
+            mapping_quality: io::MappingRecord::mapping_quality(record).unwrap_or(255),
+            sam_field: vec![format!("cm:i:{}", record.shared_min_mers)],
+            position: record.position,
+        }
+    }
+}
+
+impl From<&io::sam::Record> for Record {
+    fn from(record: &io::sam::Record) -> Self {
+        Record {
+            read_a: record.read_a.clone(),
+            length_a: record.length_a,
+            begin_a: record.begin_a,
+            end_a: record.end_a,
+            strand: record.strand,
+            read_b: record.read_b.clone(),
+            length_b: record.length_b,
+            begin_b: record.begin_b,
+            end_b: record.end_b,
+            nb_match_base: record.nb_match_base,
+            nb_base: record.nb_base,
+            mapping_quality: record.mapping_quality,
+            sam_field: record.sam_field.clone(),
+            position: record.position,
+        }
+    }
+}
+
+/// Build a paf record from a las record.
+///
+/// - nb_base is the longest alignment span and nb_match_base is nb_base - diffs
+/// - mapping_quality is set to 255 (unavailable)
+impl From<&io::las::Record> for Record {
+    fn from(record: &io::las::Record) -> Self {
+        Record {
+            read_a: record.read_a.clone(),
+            length_a: record.length_a,
+            begin_a: record.begin_a,
+            end_a: record.end_a,
+            strand: record.strand,
+            read_b: record.read_b.clone(),
+            length_b: record.length_b,
+            begin_b: record.begin_b,
+            end_b: record.end_b,
+            nb_match_base: io::MappingRecord::matches(record),
+            nb_base: io::MappingRecord::block_length(record),
+            mapping_quality: io::MappingRecord::mapping_quality(record).unwrap_or(255),
+            sam_field: Vec::new(),
+            position: record.position,
+        }
+    }
+}
+
+/// Build a paf record from a gaf record, path is used as target name.
+impl From<&io::gaf::Record> for Record {
+    fn from(record: &io::gaf::Record) -> Self {
+        Record {
+            read_a: record.read_a.clone(),
+            length_a: record.length_a,
+            begin_a: record.begin_a,
+            end_a: record.end_a,
+            strand: record.strand,
+            read_b: record.path.clone(),
+            length_b: record.path_length,
+            begin_b: record.path_begin,
+            end_b: record.path_end,
+            nb_match_base: record.nb_match_base,
+            nb_base: record.nb_base,
+            mapping_quality: record.mapping_quality,
+            sam_field: record.sam_field.clone(),
+            position: record.position,
+        }
+    }
+}
+
 type RecordInner = (
     String,
     u64,
@@ -369,4 +494,39 @@ mod test {
         }
         assert_eq!(writer.inner.into_inner().unwrap(), PAF_SAM_FIELD_FILE);
     }
+
+    #[test]
+    fn from_m4() {
+        let mut reader = io::m4::Reader::new(&b"1 2 0.1 2 0 100 450 1000 1 550 900 1000\n"[..]);
+        let m4 = reader.records().next().unwrap().unwrap();
+
+        let record = Record::from(&m4);
+
+        assert_eq!(record.read_a, "1");
+        assert_eq!(record.length_a, 1000);
+        assert_eq!(record.begin_a, 100);
+        assert_eq!(record.end_a, 450);
+        assert_eq!(record.strand, '-');
+        assert_eq!(record.read_b, "2");
+        assert_eq!(record.length_b, 1000);
+        assert_eq!(record.begin_b, 550);
+        assert_eq!(record.end_b, 900);
+        assert_eq!(record.nb_match_base, 315);
+        assert_eq!(record.nb_base, 350);
+        assert_eq!(record.mapping_quality, 255);
+        assert_eq!(record.sam_field, vec!["cm:i:2"]);
+    }
+
+    #[test]
+    fn accessors() {
+        let mut reader = Reader::new(PAF_SAM_FIELD_FILE);
+        let record = reader.records().next().unwrap().unwrap();
+
+        let record: &dyn io::MappingRecord = &record;
+        assert_eq!(record.matches(), 4500);
+        assert_eq!(record.block_length(), 4500);
+        assert_eq!(record.identity(), 1.0);
+        assert_eq!(record.mapping_quality(), Some(255));
+        assert_eq!(record.tag("am"), None);
+    }
 }
@@ -0,0 +1,816 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/* local use */
+use crate::io;
+
+/* standard use */
+use std::cmp::min;
+use std::collections::HashMap;
+
+const BAM_CIGAR_OP: &[u8; 9] = b"MIDNSHP=X";
+
+//          length operation
+type Cigar = Vec<(u64, u8)>;
+
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub read_a: String,
+    pub length_a: u64,
+    pub begin_a: u64,
+    pub end_a: u64,
+    pub strand: char,
+    pub read_b: String,
+    pub length_b: u64,
+    pub begin_b: u64,
+    pub end_b: u64,
+    pub nb_match_base: u64,
+    pub nb_base: u64,
+    pub mapping_quality: u64,
+    pub sam_field: Vec<String>,
+    pub position: (u64, u64),
+}
+
+impl Record {
+    /// Build a record from an alignment, return None if query isn't mapped.
+    ///
+    /// Query coordinates are computed from the cigar and are given on the forward strand of query, like in paf.
+    #[allow(clippy::too_many_arguments)]
+    fn from_alignment(
+        read_a: String,
+        flag: u16,
+        read_b: String,
+        length_b: u64,
+        begin_b: u64,
+        mapping_quality: u64,
+        cigar: &[(u64, u8)],
+        sam_field: Vec<String>,
+        position: (u64, u64),
+    ) -> Option<Self> {
+        if flag & 0x4 != 0 || cigar.is_empty() {
+            return None;
+        }
+
+        let mut clip_begin = 0;
+        let mut clip_end = 0;
+        let mut query_len = 0;
+        let mut target_len = 0;
+        let mut nb_base = 0;
+        let mut nb_m = 0;
+        let mut nb_equal = 0;
+        let mut use_equal = false;
+        for (len, op) in cigar {
+            match op {
+                b'S' | b'H' => {
+                    if query_len == 0 && target_len == 0 {
+                        clip_begin += len;
+                    } else {
+                        clip_end += len;
+                    }
+                }
+                b'M' => {
+                    query_len += len;
+                    target_len += len;
+                    nb_base += len;
+                    nb_m += len;
+                }
+                b'=' | b'X' => {
+                    query_len += len;
+                    target_len += len;
+                    nb_base += len;
+                    use_equal = true;
+                    if *op == b'=' {
+                        nb_equal += len;
+                    }
+                }
+                b'I' => {
+                    query_len += len;
+                    nb_base += len;
+                }
+                b'D' => {
+                    target_len += len;
+                    nb_base += len;
+                }
+                b'N' => target_len += len,
+                _ => (),
+            }
+        }
+
+        let nb_match_base = if use_equal {
+            nb_equal
+        } else if let Some(io::tag::Value::Int(nm)) = io::tag::find(&sam_field, "NM") {
+            nb_base.saturating_sub(nm as u64)
+        } else {
+            nb_m
+        };
+
+        let (strand, begin_a) = if flag & 0x10 != 0 {
+            ('-', clip_end)
+        } else {
+            ('+', clip_begin)
+        };
+
+        Some(Record {
+            read_a,
+            length_a: clip_begin + query_len + clip_end,
+            begin_a,
+            end_a: begin_a + query_len,
+            strand,
+            read_b,
+            length_b,
+            begin_b,
+            end_b: begin_b + target_len,
+            nb_match_base,
+            nb_base,
+            mapping_quality,
+            sam_field,
+            position,
+        })
+    }
+}
+
+impl io::MappingRecord for Record {
+    fn read_a(&self) -> String {
+        self.read_a.clone()
+    }
+
+    fn length_a(&self) -> u64 {
+        self.length_a
+    }
+
+    fn begin_a(&self) -> u64 {
+        self.begin_a
+    }
+
+    fn end_a(&self) -> u64 {
+        self.end_a
+    }
+
+    fn strand(&self) -> char {
+        self.strand
+    }
+
+    fn read_b(&self) -> String {
+        self.read_b.clone()
+    }
+
+    fn length_b(&self) -> u64 {
+        self.length_b
+    }
+
+    fn begin_b(&self) -> u64 {
+        self.begin_b
+    }
+
+    fn end_b(&self) -> u64 {
+        self.end_b
+    }
+
+    fn position(&self) -> (u64, u64) {
+        self.position
+    }
+
+    fn set_position(&mut self, p: (u64, u64)) {
+        self.position = p;
+    }
+
+    fn length(&self) -> u64 {
+        min(self.end_a - self.begin_a, self.end_b - self.begin_b)
+    }
+
+    fn matches(&self) -> u64 {
+        self.nb_match_base
+    }
+
+    fn block_length(&self) -> u64 {
+        self.nb_base
+    }
+
+    fn identity(&self) -> f64 {
+        if self.nb_base == 0 {
+            0.0
+        } else {
+            self.nb_match_base as f64 / self.nb_base as f64
+        }
+    }
+
+    fn mapping_quality(&self) -> Option<u64> {
+        Some(self.mapping_quality)
+    }
+
+    fn tag(&self, name: &str) -> Option<io::tag::Value> {
+        io::tag::find(&self.sam_field, name)
+    }
+
+    fn len_to_end_a(&self) -> u64 {
+        self.length_a - self.end_a
+    }
+
+    fn len_to_end_b(&self) -> u64 {
+        self.length_b - self.end_b
+    }
+
+    fn set_read_a(&mut self, new_name: String) {
+        self.read_a = new_name;
+    }
+    fn set_read_b(&mut self, new_name: String) {
+        self.read_b = new_name;
+    }
+}
+
+fn error(message: String) -> csv::Error {
+    csv::Error::from(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        message,
+    ))
+}
+
+fn parse_cigar(cigar: &str) -> Option<Cigar> {
+    if cigar == "*" {
+        return Some(Vec::new());
+    }
+
+    let mut ops = Vec::new();
+    let mut len = 0;
+    for c in cigar.bytes() {
+        if c.is_ascii_digit() {
+            len = len * 10 + (c - b'0') as u64;
+        } else if BAM_CIGAR_OP.contains(&c) {
+            ops.push((len, c));
+            len = 0;
+        } else {
+            return None;
+        }
+    }
+
+    Some(ops)
+}
+
+pub struct Records<'a, R: 'a + std::io::Read> {
+    inner: csv::StringRecordsIter<'a, R>,
+    reference_length: HashMap<String, u64>,
+}
+
+impl<'a, R: std::io::Read> Iterator for Records<'a, R> {
+    type Item = csv::Result<Record>;
+
+    fn next(&mut self) -> Option<csv::Result<Record>> {
+        loop {
+            let position = self.inner.reader().position().byte();
+            let line = match self.inner.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            let new_position = self.inner.reader().position().byte();
+
+            if line[0].starts_with('@') {
+                if &line[0] == "@SQ" {
+                    let name = line.iter().find_map(|f| f.strip_prefix("SN:"));
+                    let length = line
+                        .iter()
+                        .find_map(|f| f.strip_prefix("LN:"))
+                        .and_then(|l| l.parse::<u64>().ok());
+
+                    match (name, length) {
+                        (Some(n), Some(l)) => {
+                            self.reference_length.insert(n.to_string(), l);
+                        }
+                        _ => {
+                            return Some(Err(error(format!("Invalid sam header line {:?}", line))))
+                        }
+                    }
+                }
+                continue;
+            }
+
+            if line.len() < 11 {
+                return Some(Err(error(format!("Invalid sam record {:?}", line))));
+            }
+
+            let flag = line[1].parse::<u16>();
+            let begin_b = line[3].parse::<u64>();
+            let mapping_quality = line[4].parse::<u64>();
+            let cigar = parse_cigar(&line[5]);
+            let (flag, begin_b, mapping_quality, cigar) =
+                match (flag, begin_b, mapping_quality, cigar) {
+                    (Ok(f), Ok(b), Ok(m), Some(c)) => (f, b.saturating_sub(1), m, c),
+                    _ => return Some(Err(error(format!("Invalid sam record {:?}", line)))),
+                };
+
+            if flag & 0x4 != 0 || &line[2] == "*" {
+                continue;
+            }
+
+            let length_b = match self.reference_length.get(&line[2]) {
+                Some(l) => *l,
+                None => {
+                    return Some(Err(error(format!(
+                        "Reference {} isn't present in sam header",
+                        &line[2]
+                    ))))
+                }
+            };
+
+            let sam_field = line.iter().skip(11).map(|f| f.to_string()).collect();
+
+            if let Some(record) = Record::from_alignment(
+                line[0].to_string(),
+                flag,
+                line[2].to_string(),
+                length_b,
+                begin_b,
+                mapping_quality,
+                &cigar,
+                sam_field,
+                (position, new_position),
+            ) {
+                return Some(Ok(record));
+            }
+        }
+    }
+}
+
+pub struct Reader<R: std::io::Read> {
+    inner: csv::Reader<R>,
+}
+
+impl<R: std::io::Read> Reader<R> {
+    pub fn new(reader: R) -> Self {
+        Reader {
+            inner: csv::ReaderBuilder::new()
+                .delimiter(b'\t')
+                .has_headers(false)
+                .flexible(true)
+                .quoting(false)
+                .from_reader(reader),
+        }
+    }
+
+    /// Iterate over all mapped records.
+    pub fn records(&mut self) -> Records<'_, R> {
+        Records {
+            inner: self.inner.records(),
+            reference_length: HashMap::new(),
+        }
+    }
+}
+
+fn le_u16(buf: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([buf[offset], buf[offset + 1]])
+}
+
+fn le_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        buf[offset],
+        buf[offset + 1],
+        buf[offset + 2],
+        buf[offset + 3],
+    ])
+}
+
+/// Convert bam auxiliary data in sam text fields, CG tag is returned as a cigar.
+fn parse_bam_tags(mut data: &[u8]) -> Option<(Vec<String>, Option<Cigar>)> {
+    let mut fields = Vec::new();
+    let mut long_cigar = None;
+
+    while data.len() >= 3 {
+        let tag = String::from_utf8_lossy(&data[0..2]).to_string();
+        let value_type = data[2];
+        data = &data[3..];
+
+        let (text, used) = match value_type {
+            b'A' => (format!("{}:A:{}", tag, *data.first()? as char), 1),
+            b'c' => (format!("{}:i:{}", tag, *data.first()? as i8), 1),
+            b'C' => (format!("{}:i:{}", tag, *data.first()?), 1),
+            b's' if data.len() >= 2 => (format!("{}:i:{}", tag, le_u16(data, 0) as i16), 2),
+            b'S' if data.len() >= 2 => (format!("{}:i:{}", tag, le_u16(data, 0)), 2),
+            b'i' if data.len() >= 4 => (format!("{}:i:{}", tag, le_u32(data, 0) as i32), 4),
+            b'I' if data.len() >= 4 => (format!("{}:i:{}", tag, le_u32(data, 0)), 4),
+            b'f' if data.len() >= 4 => {
+                (format!("{}:f:{}", tag, f32::from_bits(le_u32(data, 0))), 4)
+            }
+            b'Z' | b'H' => {
+                let end = data.iter().position(|c| *c == 0)?;
+                (
+                    format!(
+                        "{}:{}:{}",
+                        tag,
+                        value_type as char,
+                        String::from_utf8_lossy(&data[..end])
+                    ),
+                    end + 1,
+                )
+            }
+            b'B' if data.len() >= 5 => {
+                let subtype = data[0];
+                let count = le_u32(data, 1) as usize;
+                let size = match subtype {
+                    b'c' | b'C' => 1,
+                    b's' | b'S' => 2,
+                    b'i' | b'I' | b'f' => 4,
+                    _ => return None,
+                };
+                if data.len() < 5 + count * size {
+                    return None;
+                }
+
+                if tag == "CG" && subtype == b'I' {
+                    long_cigar = Some(
+                        (0..count)
+                            .map(|i| {
+                                let op = le_u32(data, 5 + i * 4);
+                                ((op >> 4) as u64, BAM_CIGAR_OP[(op & 0xf) as usize % 9])
+                            })
+                            .collect(),
+                    );
+                    data = &data[5 + count * size..];
+                    continue;
+                }
+
+                let values: Vec<String> = (0..count)
+                    .map(|i| {
+                        let offset = 5 + i * size;
+                        match subtype {
+                            b'c' => (data[offset] as i8).to_string(),
+                            b'C' => data[offset].to_string(),
+                            b's' => (le_u16(data, offset) as i16).to_string(),
+                            b'S' => le_u16(data, offset).to_string(),
+                            b'i' => (le_u32(data, offset) as i32).to_string(),
+                            b'I' => le_u32(data, offset).to_string(),
+                            _ => f32::from_bits(le_u32(data, offset)).to_string(),
+                        }
+                    })
+                    .collect();
+
+                (
+                    format!("{}:B:{},{}", tag, subtype as char, values.join(",")),
+                    5 + count * size,
+                )
+            }
+            _ => return None,
+        };
+
+        fields.push(text);
+        data = &data[used..];
+    }
+
+    Some((fields, long_cigar))
+}
+
+pub struct BamRecords<'a, R: 'a + std::io::Read> {
+    reader: &'a mut BamReader<R>,
+}
+
+impl<'a, R: std::io::Read> BamRecords<'a, R> {
+    fn read_header(&mut self) -> std::io::Result<()> {
+        let mut magic = [0u8; 8];
+        self.reader.read_exact(&mut magic)?;
+        if &magic[0..4] != b"BAM\x01" {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Input isn't a bam file",
+            ));
+        }
+
+        let mut text = vec![0u8; le_u32(&magic, 4) as usize];
+        self.reader.read_exact(&mut text)?;
+
+        let mut buf = [0u8; 4];
+        self.reader.read_exact(&mut buf)?;
+        for _ in 0..le_u32(&buf, 0) {
+            self.reader.read_exact(&mut buf)?;
+            let mut name = vec![0u8; le_u32(&buf, 0) as usize];
+            self.reader.read_exact(&mut name)?;
+            self.reader.read_exact(&mut buf)?;
+
+            let name = String::from_utf8_lossy(&name)
+                .trim_end_matches('\0')
+                .to_string();
+            self.reader.references.push((name, le_u32(&buf, 0) as u64));
+        }
+
+        Ok(())
+    }
+
+    /// Read next alignment block, return None at end of file.
+    fn read_block(&mut self) -> std::io::Result<Option<Vec<u8>>> {
+        let mut buf = [0u8; 4];
+        let mut read = 0;
+        while read < 4 {
+            let nb = self.reader.inner.read(&mut buf[read..])?;
+            if nb == 0 {
+                if read == 0 {
+                    return Ok(None);
+                }
+                return Err(std::io::ErrorKind::UnexpectedEof.into());
+            }
+            read += nb;
+        }
+        self.reader.position += 4;
+
+        let mut block = vec![0u8; le_u32(&buf, 0) as usize];
+        self.reader.read_exact(&mut block)?;
+
+        Ok(Some(block))
+    }
+
+    fn parse_block(&self, block: &[u8], position: (u64, u64)) -> csv::Result<Option<Record>> {
+        let invalid = || error("Invalid bam record".to_string());
+        if block.len() < 32 {
+            return Err(invalid());
+        }
+
+        let ref_id = le_u32(block, 0) as i32;
+        let begin_b = le_u32(block, 4) as u64;
+        let l_read_name = block[8] as usize;
+        let mapping_quality = block[9] as u64;
+        let n_cigar_op = le_u16(block, 12) as usize;
+        let flag = le_u16(block, 14);
+        let l_seq = le_u32(block, 16) as usize;
+
+        let cigar_begin = 32 + l_read_name;
+        let tags_begin = cigar_begin + n_cigar_op * 4 + (l_seq + 1) / 2 + l_seq;
+        if block.len() < tags_begin {
+            return Err(invalid());
+        }
+
+        if flag & 0x4 != 0 || ref_id < 0 {
+            return Ok(None);
+        }
+
+        let (read_b, length_b) = self
+            .reader
+            .references
+            .get(ref_id as usize)
+            .ok_or_else(invalid)?
+            .clone();
+
+        let read_a = String::from_utf8_lossy(&block[32..cigar_begin])
+            .trim_end_matches('\0')
+            .to_string();
+
+        let mut cigar: Cigar = (0..n_cigar_op)
+            .map(|i| {
+                let op = le_u32(block, cigar_begin + i * 4);
+                ((op >> 4) as u64, BAM_CIGAR_OP[(op & 0xf) as usize % 9])
+            })
+            .collect();
+
+        let (sam_field, long_cigar) = parse_bam_tags(&block[tags_begin..]).ok_or_else(invalid)?;
+        if let Some(c) = long_cigar {
+            cigar = c;
+        }
+
+        Ok(Record::from_alignment(
+            read_a,
+            flag,
+            read_b,
+            length_b,
+            begin_b,
+            mapping_quality,
+            &cigar,
+            sam_field,
+            position,
+        ))
+    }
+}
+
+impl<'a, R: std::io::Read> Iterator for BamRecords<'a, R> {
+    type Item = csv::Result<Record>;
+
+    fn next(&mut self) -> Option<csv::Result<Record>> {
+        if !self.reader.header_read {
+            self.reader.header_read = true;
+            if let Err(e) = self.read_header() {
+                return Some(Err(csv::Error::from(e)));
+            }
+        }
+
+        loop {
+            let position = self.reader.position;
+            let block = match self.read_block() {
+                Ok(Some(b)) => b,
+                Ok(None) => return None,
+                Err(e) => return Some(Err(csv::Error::from(e))),
+            };
+
+            match self.parse_block(&block, (position, self.reader.position)) {
+                Ok(Some(record)) => return Some(Ok(record)),
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Reader of bam, input must be already decompressed.
+pub struct BamReader<R: std::io::Read> {
+    inner: R,
+    position: u64,
+    header_read: bool,
+    references: Vec<(String, u64)>,
+}
+
+impl<R: std::io::Read> BamReader<R> {
+    pub fn new(reader: R) -> Self {
+        BamReader {
+            inner: reader,
+            position: 0,
+            header_read: false,
+            references: Vec::new(),
+        }
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> std::io::Result<()> {
+        self.inner.read_exact(buf)?;
+        self.position += buf.len() as u64;
+
+        Ok(())
+    }
+
+    /// Iterate over all mapped records.
+    pub fn records(&mut self) -> BamRecords<'_, R> {
+        BamRecords { reader: self }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    const SAM_FILE: &[u8] = b"@HD\tVN:1.6\tSO:unsorted
+@SQ\tSN:2\tLN:10000
+@SQ\tSN:3\tLN:10000
+1\t0\t2\t5501\t60\t20S4480M7500S\t*\t0\t0\t*\t*\tNM:i:10\ttp:A:P
+1\t16\t3\t1\t60\t2000H4500M5500H\t*\t0\t0\t*\t*\tNM:i:0
+4\t4\t*\t0\t0\t*\t*\t0\t0\t*\t*
+1\t0\t3\t101\t60\t10=1X10=5S\t*\t0\t0\t*\t*
+";
+
+    const READ_A: &[&str; 3] = &["1", "1", "1"];
+    const LENGTH_A: &[u64; 3] = &[12000, 12000, 26];
+    const BEGIN_A: &[u64; 3] = &[20, 5500, 0];
+    const END_A: &[u64; 3] = &[4500, 10000, 21];
+    const STRAND: &[char; 3] = &['+', '-', '+'];
+    const READ_B: &[&str; 3] = &["2", "3", "3"];
+    const LENGTH_B: &[u64; 3] = &[10000, 10000, 10000];
+    const BEGIN_B: &[u64; 3] = &[5500, 0, 100];
+    const END_B: &[u64; 3] = &[9980, 4500, 121];
+    const NB_MATCH_BASE: &[u64; 3] = &[4470, 4500, 20];
+    const NB_BASE: &[u64; 3] = &[4480, 4500, 21];
+    const MAPPING_QUALITY: &[u64; 3] = &[60, 60, 60];
+
+    #[test]
+    fn read() {
+        let mut reader = Reader::new(SAM_FILE);
+
+        let mut nb_record = 0;
+        for (i, r) in reader.records().enumerate() {
+            let record = r.unwrap();
+
+            assert_eq!(record.read_a, READ_A[i]);
+            assert_eq!(record.length_a, LENGTH_A[i]);
+            assert_eq!(record.begin_a, BEGIN_A[i]);
+            assert_eq!(record.end_a, END_A[i]);
+            assert_eq!(record.strand, STRAND[i]);
+            assert_eq!(record.read_b, READ_B[i]);
+            assert_eq!(record.length_b, LENGTH_B[i]);
+            assert_eq!(record.begin_b, BEGIN_B[i]);
+            assert_eq!(record.end_b, END_B[i]);
+            assert_eq!(record.nb_match_base, NB_MATCH_BASE[i]);
+            assert_eq!(record.nb_base, NB_BASE[i]);
+            assert_eq!(record.mapping_quality, MAPPING_QUALITY[i]);
+
+            nb_record += 1;
+        }
+
+        assert_eq!(nb_record, 3);
+    }
+
+    #[test]
+    fn read_missing_reference() {
+        let mut reader = Reader::new(&b"1\t0\t2\t1\t60\t10M\t*\t0\t0\t*\t*\n"[..]);
+
+        assert!(reader.records().next().unwrap().is_err());
+    }
+
+    fn bam_record(
+        name: &str,
+        flag: u16,
+        ref_id: i32,
+        pos: i32,
+        cigar: &[(u32, u32)],
+        tags: &[u8],
+    ) -> Vec<u8> {
+        let l_seq: u32 = 0;
+        let mut block = Vec::new();
+        block.extend_from_slice(&ref_id.to_le_bytes());
+        block.extend_from_slice(&pos.to_le_bytes());
+        block.push(name.len() as u8 + 1);
+        block.push(60);
+        block.extend_from_slice(&0u16.to_le_bytes());
+        block.extend_from_slice(&(cigar.len() as u16).to_le_bytes());
+        block.extend_from_slice(&flag.to_le_bytes());
+        block.extend_from_slice(&l_seq.to_le_bytes());
+        block.extend_from_slice(&(-1i32).to_le_bytes());
+        block.extend_from_slice(&(-1i32).to_le_bytes());
+        block.extend_from_slice(&0i32.to_le_bytes());
+        block.extend_from_slice(name.as_bytes());
+        block.push(0);
+        for (len, op) in cigar {
+            block.extend_from_slice(&(len << 4 | op).to_le_bytes());
+        }
+        block.extend_from_slice(tags);
+
+        let mut record = (block.len() as u32).to_le_bytes().to_vec();
+        record.extend(block);
+        record
+    }
+
+    fn bam_file() -> Vec<u8> {
+        let text = b"@HD\tVN:1.6\n";
+        let mut file = b"BAM\x01".to_vec();
+        file.extend_from_slice(&(text.len() as u32).to_le_bytes());
+        file.extend_from_slice(text);
+        file.extend_from_slice(&2u32.to_le_bytes());
+        for (name, len) in &[("2", 10000u32), ("3", 10000u32)] {
+            file.extend_from_slice(&(name.len() as u32 + 1).to_le_bytes());
+            file.extend_from_slice(name.as_bytes());
+            file.push(0);
+            file.extend_from_slice(&len.to_le_bytes());
+        }
+
+        // 20S4480M7500S with NM:i:10 and tp:A:P
+        let mut tags = b"NMC\x0a".to_vec();
+        tags.extend_from_slice(b"tpAP");
+        file.extend(bam_record(
+            "1",
+            0,
+            0,
+            5500,
+            &[(20, 4), (4480, 0), (7500, 4)],
+            &tags,
+        ));
+
+        // unmapped
+        file.extend(bam_record("4", 4, -1, -1, &[], b""));
+
+        // 2000H4500M5500H stored in CG tag
+        let mut tags = b"CGBI".to_vec();
+        tags.extend_from_slice(&3u32.to_le_bytes());
+        for op in &[2000u32 << 4 | 5, 4500 << 4, 5500 << 4 | 5] {
+            tags.extend_from_slice(&op.to_le_bytes());
+        }
+        file.extend(bam_record("1", 16, 1, 0, &[(0, 4), (4500, 3)], &tags));
+
+        file
+    }
+
+    #[test]
+    fn read_bam() {
+        let file = bam_file();
+        let mut reader = BamReader::new(&file[..]);
+
+        let records: Vec<Record> = reader.records().map(|r| r.unwrap()).collect();
+        assert_eq!(records.len(), 2);
+
+        for (i, record) in records.iter().enumerate() {
+            assert_eq!(record.read_a, READ_A[i]);
+            assert_eq!(record.length_a, LENGTH_A[i]);
+            assert_eq!(record.begin_a, BEGIN_A[i]);
+            assert_eq!(record.end_a, END_A[i]);
+            assert_eq!(record.strand, STRAND[i]);
+            assert_eq!(record.read_b, READ_B[i]);
+            assert_eq!(record.length_b, LENGTH_B[i]);
+            assert_eq!(record.begin_b, BEGIN_B[i]);
+            assert_eq!(record.end_b, END_B[i]);
+            assert_eq!(record.nb_match_base, NB_MATCH_BASE[i]);
+            assert_eq!(record.nb_base, NB_BASE[i]);
+            assert_eq!(record.mapping_quality, MAPPING_QUALITY[i]);
+        }
+
+        assert_eq!(records[0].sam_field, vec!["NM:i:10", "tp:A:P"]);
+        assert!(records[1].sam_field.is_empty());
+        assert_eq!(records[1].position.1, file.len() as u64);
+    }
+}
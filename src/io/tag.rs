@@ -0,0 +1,162 @@
+/*
+Copyright (c) 2018 Pierre Marijon <pierre.marijon@inria.fr>
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/// Value of a sam like optional field `TAG:TYPE:VALUE`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Char(char),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Hex(String),
+    IntArray(Vec<i64>),
+    FloatArray(Vec<f64>),
+}
+
+impl Value {
+    /// Numeric value of tag, None for non numeric tag.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Int(v) => Some(*v as f64),
+            Value::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Value::Char(v) => write!(f, "{}", v),
+            Value::Int(v) => write!(f, "{}", v),
+            Value::Float(v) => write!(f, "{}", v),
+            Value::String(v) | Value::Hex(v) => write!(f, "{}", v),
+            Value::IntArray(v) => write!(
+                f,
+                "{}",
+                v.iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            Value::FloatArray(v) => write!(
+                f,
+                "{}",
+                v.iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        }
+    }
+}
+
+/// Parse a field `TAG:TYPE:VALUE`, return None if field isn't a valid tag.
+pub fn parse(field: &str) -> Option<(&str, Value)> {
+    let mut parts = field.splitn(3, ':');
+    let name = parts.next()?;
+    let value_type = parts.next()?;
+    let value = parts.next()?;
+
+    let value = match value_type {
+        "A" => Value::Char(value.chars().next()?),
+        "i" => Value::Int(value.parse::<i64>().ok()?),
+        "f" => Value::Float(value.parse::<f64>().ok()?),
+        "Z" => Value::String(value.to_string()),
+        "H" => Value::Hex(value.to_string()),
+        "B" => {
+            let mut values = value.split(',');
+            match values.next()? {
+                "f" => Value::FloatArray(
+                    values
+                        .map(|v| v.parse::<f64>().ok())
+                        .collect::<Option<Vec<f64>>>()?,
+                ),
+                "c" | "C" | "s" | "S" | "i" | "I" => Value::IntArray(
+                    values
+                        .map(|v| v.parse::<i64>().ok())
+                        .collect::<Option<Vec<i64>>>()?,
+                ),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    Some((name, value))
+}
+
+/// Find tag with this name in fields and parse its value.
+pub fn find(fields: &[String], name: &str) -> Option<Value> {
+    fields
+        .iter()
+        .filter_map(|field| parse(field))
+        .find(|(n, _)| *n == name)
+        .map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    #[test]
+    fn types() {
+        assert_eq!(parse("tp:A:P"), Some(("tp", Value::Char('P'))));
+        assert_eq!(parse("cm:i:-12"), Some(("cm", Value::Int(-12))));
+        assert_eq!(parse("dv:f:0.0125"), Some(("dv", Value::Float(0.0125))));
+        assert_eq!(
+            parse("cg:Z:10M2I"),
+            Some(("cg", Value::String("10M2I".to_string())))
+        );
+        assert_eq!(
+            parse("xx:H:1AE3"),
+            Some(("xx", Value::Hex("1AE3".to_string())))
+        );
+        assert_eq!(
+            parse("xx:B:i,1,-2,3"),
+            Some(("xx", Value::IntArray(vec![1, -2, 3])))
+        );
+        assert_eq!(
+            parse("xx:B:f,0.5,1.5"),
+            Some(("xx", Value::FloatArray(vec![0.5, 1.5])))
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(parse("tp"), None);
+        assert_eq!(parse("cm:i:abc"), None);
+        assert_eq!(parse("xx:Q:1"), None);
+        assert_eq!(parse("xx:B:q,1"), None);
+    }
+
+    #[test]
+    fn lookup() {
+        let fields = vec!["tp:A:P".to_string(), "cm:i:12".to_string()];
+
+        assert_eq!(find(&fields, "cm"), Some(Value::Int(12)));
+        assert_eq!(find(&fields, "cm").unwrap().as_f64(), Some(12.0));
+        assert_eq!(find(&fields, "tp").unwrap().as_f64(), None);
+        assert_eq!(find(&fields, "s1"), None);
+    }
+}
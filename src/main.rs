@@ -50,14 +50,24 @@ fn main() {
     /* Manage input and output file */
     let (input, compression) = file::get_input(matches.value_of("input").unwrap());
 
-    let format = if matches.is_present("format") {
-        match matches.value_of("format").unwrap() {
-            "paf" => io::MappingFormat::Paf,
-            "m4" => io::MappingFormat::M4,
-            _ => io::MappingFormat::Paf,
-        }
+    let (format, input) = if matches.is_present("format") {
+        (
+            io::MappingFormat::from(matches.value_of("format").unwrap()),
+            input,
+        )
+    } else if matches.value_of("input").unwrap().ends_with(".las") {
+        (io::MappingFormat::Las, input)
+    } else {
+        io::detect_format(input).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        })
+    };
+
+    let output_format = if matches.is_present("output-format") {
+        io::MappingFormat::from(matches.value_of("output-format").unwrap())
     } else {
-        io::MappingFormat::Paf
+        format
     };
 
     let out_compression = file::choose_compression(
@@ -69,6 +79,7 @@ fn main() {
     let output: std::io::BufWriter<Box<dyn std::io::Write>> = std::io::BufWriter::new(
         file::get_output(matches.value_of("output").unwrap(), out_compression),
     );
+    let writer = io::Writer::new(output, output_format);
 
     let internal_match_threshold = matches
         .value_of("internal-match-threshold")
@@ -76,23 +87,157 @@ fn main() {
         .parse::<f64>()
         .unwrap();
 
+    let max_overhang = matches
+        .value_of("max-overhang")
+        .unwrap()
+        .parse::<u64>()
+        .unwrap();
+
+    let stateful = cli::Stateful::new(internal_match_threshold, max_overhang, &subcmd);
+
+    // filters that need all mapping read input twice, standard input is copied in a temporary file
+    let input_name = matches.value_of("input").unwrap();
+    let mut temporary = None;
+    let (input, second_input) = if stateful.streaming() {
+        (input, None)
+    } else if input_name == "-" {
+        let copy = file::copy_to_temporary(input);
+        let path_name = copy.path().to_str().unwrap().to_string();
+        temporary = Some(copy);
+
+        (
+            file::get_input(&path_name).0,
+            Some(file::get_input(&path_name).0),
+        )
+    } else {
+        (input, Some(file::get_input(input_name).0))
+    };
+
     match format {
-        io::MappingFormat::Paf => paf(input, output, internal_match_threshold, subcmd),
-        io::MappingFormat::M4 => m4(input, output, internal_match_threshold, subcmd),
+        io::MappingFormat::Paf => run(
+            io::paf::Reader::new(input).records(),
+            second_input
+                .map(io::paf::Reader::new)
+                .as_mut()
+                .map(|r| r.records()),
+            writer,
+            stateful,
+            internal_match_threshold,
+            max_overhang,
+            subcmd,
+        ),
+        io::MappingFormat::Gaf => run(
+            io::gaf::Reader::new(input).records(),
+            second_input
+                .map(io::gaf::Reader::new)
+                .as_mut()
+                .map(|r| r.records()),
+            writer,
+            stateful,
+            internal_match_threshold,
+            max_overhang,
+            subcmd,
+        ),
+        io::MappingFormat::M4 => run(
+            io::m4::Reader::new(input).records(),
+            second_input
+                .map(io::m4::Reader::new)
+                .as_mut()
+                .map(|r| r.records()),
+            writer,
+            stateful,
+            internal_match_threshold,
+            max_overhang,
+            subcmd,
+        ),
+        io::MappingFormat::Mhap => run(
+            io::mhap::Reader::new(input).records(),
+            second_input
+                .map(io::mhap::Reader::new)
+                .as_mut()
+                .map(|r| r.records()),
+            writer,
+            stateful,
+            internal_match_threshold,
+            max_overhang,
+            subcmd,
+        ),
+        io::MappingFormat::Sam => run(
+            io::sam::Reader::new(input).records(),
+            second_input
+                .map(io::sam::Reader::new)
+                .as_mut()
+                .map(|r| r.records()),
+            writer,
+            stateful,
+            internal_match_threshold,
+            max_overhang,
+            subcmd,
+        ),
+        io::MappingFormat::Las => {
+            let reads = matches
+                .value_of("las-reads")
+                .map(|path| std::fs::File::open(path).and_then(io::las::read_names))
+                .unwrap_or_else(|| {
+                    Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        "Las input require a read table, use --las-reads",
+                    ))
+                })
+                .unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                });
+
+            run(
+                io::las::Reader::new(input, reads.clone()).records(),
+                second_input
+                    .map(|i| io::las::Reader::new(i, reads))
+                    .as_mut()
+                    .map(|r| r.records()),
+                writer,
+                stateful,
+                internal_match_threshold,
+                max_overhang,
+                subcmd,
+            )
+        }
+        io::MappingFormat::Bam => run(
+            io::sam::BamReader::new(input).records(),
+            second_input
+                .map(io::sam::BamReader::new)
+                .as_mut()
+                .map(|r| r.records()),
+            writer,
+            stateful,
+            internal_match_threshold,
+            max_overhang,
+            subcmd,
+        ),
     }
+
+    // temporary file is removed on drop
+    drop(temporary);
 }
 
-fn paf(
-    input: Box<dyn std::io::Read>,
-    output: std::io::BufWriter<Box<dyn std::io::Write>>,
+fn run<R, I>(
+    records: I,
+    second_pass: Option<I>,
+    mut writer: io::Writer<std::io::BufWriter<Box<dyn std::io::Write>>>,
+    mut stateful: cli::Stateful,
     internal_match_threshold: f64,
+    max_overhang: u64,
     subcmd: std::collections::HashMap<String, clap::ArgMatches>,
-) {
-    let mut writer = io::paf::Writer::new(output);
-    let mut reader = io::paf::Reader::new(input);
-    let drop = cli::Drop::new(internal_match_threshold, &subcmd);
-    let keep = cli::Keep::new(internal_match_threshold, &subcmd);
-    let mut modifier = cli::Modifier::new(internal_match_threshold, &subcmd);
+) where
+    R: MappingRecord,
+    I: Iterator<Item = csv::Result<R>>,
+    for<'a> io::paf::Record: From<&'a R>,
+    for<'a> io::m4::Record: From<&'a R>,
+    for<'a> io::gaf::Record: From<&'a R>,
+{
+    let drop = cli::Drop::new(internal_match_threshold, max_overhang, &subcmd);
+    let keep = cli::Keep::new(internal_match_threshold, max_overhang, &subcmd);
+    let mut modifier = cli::Modifier::new(internal_match_threshold, max_overhang, &subcmd);
 
     let mut index = if let Some(m) = subcmd.get("index") {
         generator::Indexing::new(m.value_of("filename").unwrap(), m.value_of("type").unwrap())
@@ -101,19 +246,7 @@ fn paf(
     };
 
     let mut position = 0;
-    for result in reader.records() {
-        let mut record = result.expect("Trouble during read of input mapping");
-
-        // keep
-        if !keep.pass(&record) {
-            continue;
-        }
-
-        // drop
-        if !drop.pass(&record) {
-            continue;
-        }
-
+    let mut output = |mut record: R| {
         // modifier
         modifier.pass(&mut record);
 
@@ -127,59 +260,38 @@ fn paf(
         index.run(&mut record);
 
         position = new_position;
-    }
-
-    // close modifier
-    modifier.write();
-
-    index.write();
-}
-
-fn m4(
-    input: Box<dyn std::io::Read>,
-    output: std::io::BufWriter<Box<dyn std::io::Write>>,
-    internal_match_threshold: f64,
-    subcmd: std::collections::HashMap<String, clap::ArgMatches>,
-) {
-    let mut writer = io::m4::Writer::new(output);
-    let mut reader = io::m4::Reader::new(input);
-    let drop = cli::Drop::new(internal_match_threshold, &subcmd);
-    let keep = cli::Keep::new(internal_match_threshold, &subcmd);
-    let mut modifier = cli::Modifier::new(internal_match_threshold, &subcmd);
+    };
 
-    let mut index = if let Some(m) = subcmd.get("index") {
-        generator::Indexing::new(m.value_of("filename").unwrap(), m.value_of("type").unwrap())
-    } else {
-        generator::Indexing::empty()
+    let passing = |records: I| {
+        records
+            .map(|result| result.expect("Trouble during read of input mapping"))
+            // keep
+            .filter(|record| keep.pass(record))
+            // drop
+            .filter(|record| drop.pass(record))
     };
 
-    let mut position = 0;
-    for result in reader.records() {
-        let mut record = result.expect("Trouble during read of input mapping");
+    if stateful.streaming() {
+        for (i, record) in passing(records).enumerate() {
+            stateful.add(i, &record);
 
-        // keep
-        if !keep.pass(&record) {
-            continue;
+            if stateful.pass(i, &record) {
+                output(record);
+            }
         }
-
-        // drop
-        if !drop.pass(&record) {
-            continue;
+    } else {
+        // first pass only give mapping to stateful filters, mapping are written during second pass
+        for (i, record) in passing(records).enumerate() {
+            stateful.add(i, &record);
         }
+        stateful.finish();
 
-        // modifier
-        modifier.pass(&mut record);
-
-        let new_position = position
-            + writer
-                .write(&record)
-                .expect("Trouble during write of output");
-
-        record.set_position((position, new_position));
-
-        index.run(&mut record);
-
-        position = new_position;
+        let second_pass = second_pass.expect("Stateful filters need to read input twice");
+        for (i, record) in passing(second_pass).enumerate() {
+            if stateful.second_pass(i, &record) {
+                output(record);
+            }
+        }
     }
 
     // close modifier
@@ -20,11 +20,15 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
  */
 
+/* project use */
+use crate::io;
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum WorkOnWichPart {
     Query,
     Target,
     Both,
+    Either,
 }
 
 impl From<&str> for WorkOnWichPart {
@@ -33,7 +37,24 @@ impl From<&str> for WorkOnWichPart {
             "query" => WorkOnWichPart::Query,
             "target" => WorkOnWichPart::Target,
             "both" => WorkOnWichPart::Both,
+            "either" => WorkOnWichPart::Either,
             _ => WorkOnWichPart::Both,
         }
     }
 }
+
+impl WorkOnWichPart {
+    /// Apply test on query, target, both (query and target) or either (query or target) of the reads.
+    pub fn test<F: Fn(&str, u64) -> bool>(&self, r: &dyn io::MappingRecord, test: F) -> bool {
+        match self {
+            WorkOnWichPart::Query => test(&r.read_a(), r.length_a()),
+            WorkOnWichPart::Target => test(&r.read_b(), r.length_b()),
+            WorkOnWichPart::Both => {
+                test(&r.read_a(), r.length_a()) && test(&r.read_b(), r.length_b())
+            }
+            WorkOnWichPart::Either => {
+                test(&r.read_a(), r.length_a()) || test(&r.read_b(), r.length_b())
+            }
+        }
+    }
+}